@@ -0,0 +1,35 @@
+use crate::{colour::Colour, ray::Ray};
+
+#[derive(Clone, Copy, Debug)]
+pub enum Background {
+    Black,
+    Solid(Colour),
+    Gradient { bottom: Colour, top: Colour },
+}
+
+impl Background {
+    pub fn sky() -> Self {
+        Background::Gradient {
+            bottom: Colour::new(1.0, 1.0, 1.0),
+            top: Colour::new(0.5, 0.7, 1.0),
+        }
+    }
+
+    pub fn colour(&self, r: &Ray) -> Colour {
+        match self {
+            Background::Black => Colour::new(0.0, 0.0, 0.0),
+            Background::Solid(colour) => *colour,
+            Background::Gradient { bottom, top } => {
+                let unit_direction = r.direction().unit_vector();
+                let a = 0.5 * (unit_direction.y() + 1.0);
+                (1.0 - a) * *bottom + a * *top
+            }
+        }
+    }
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Background::sky()
+    }
+}
@@ -0,0 +1,269 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    hittable_list::HittableList,
+    interval::Interval,
+    ray::Ray,
+};
+
+pub struct BvhNode {
+    left: Option<Box<dyn Hittable + Send + Sync>>,
+    right: Option<Box<dyn Hittable + Send + Sync>>,
+    bbox: Aabb,
+    /// Objects without finite bounds, such as planes, tested on every ray
+    /// beside the tree so they cannot skew the split costs.
+    unbounded: Vec<Box<dyn Hittable + Send + Sync>>,
+}
+
+impl BvhNode {
+    pub fn new(list: HittableList) -> Self {
+        let (bounded, unbounded) = list
+            .into_objects()
+            .into_iter()
+            .partition(|object| object.bounding_box().is_bounded());
+
+        Self {
+            unbounded,
+            ..Self::build(bounded)
+        }
+    }
+
+    fn build(mut objects: Vec<Box<dyn Hittable + Send + Sync>>) -> Self {
+        match objects.len() {
+            0 => Self {
+                left: None,
+                right: None,
+                bbox: Aabb::empty(),
+                unbounded: Vec::new(),
+            },
+            1 => {
+                let left = objects.remove(0);
+                Self {
+                    bbox: left.bounding_box(),
+                    left: Some(left),
+                    right: None,
+                    unbounded: Vec::new(),
+                }
+            }
+            2 => {
+                let right = objects.remove(1);
+                let left = objects.remove(0);
+                Self {
+                    bbox: Aabb::enclosing(&left.bounding_box(), &right.bounding_box()),
+                    left: Some(left),
+                    right: Some(right),
+                    unbounded: Vec::new(),
+                }
+            }
+            _ => {
+                let split = Self::sah_split(&mut objects);
+                let right_objects = objects.split_off(split);
+
+                let left = Self::subtree(objects);
+                let right = Self::subtree(right_objects);
+
+                Self {
+                    bbox: Aabb::enclosing(&left.bounding_box(), &right.bounding_box()),
+                    left: Some(left),
+                    right: Some(right),
+                    unbounded: Vec::new(),
+                }
+            }
+        }
+    }
+
+    fn subtree(
+        mut objects: Vec<Box<dyn Hittable + Send + Sync>>,
+    ) -> Box<dyn Hittable + Send + Sync> {
+        match objects.len() {
+            1 => objects.remove(0),
+            _ => Box::new(Self::build(objects)),
+        }
+    }
+
+    /// Sorts `objects` along the longest axis of their centroid bounds and
+    /// returns the split index minimising the surface area heuristic.
+    fn sah_split(objects: &mut [Box<dyn Hittable + Send + Sync>]) -> usize {
+        let n = objects.len();
+
+        let centroid_bounds = objects.iter().fold(Aabb::empty(), |acc, object| {
+            let c = object.bounding_box().centroid();
+            Aabb::enclosing(&acc, &Aabb::from_points(c, c))
+        });
+        let axis = centroid_bounds.longest_axis();
+
+        objects.sort_by(|a, b| {
+            let a = a.bounding_box().centroid()[axis];
+            let b = b.bounding_box().centroid()[axis];
+            a.total_cmp(&b)
+        });
+
+        let mut right_areas = vec![0.0; n];
+        let mut right_bbox = Aabb::empty();
+        for i in (1..n).rev() {
+            right_bbox = Aabb::enclosing(&right_bbox, &objects[i].bounding_box());
+            right_areas[i] = right_bbox.surface_area();
+        }
+
+        let mut best_split = n / 2;
+        let mut best_cost = f64::INFINITY;
+        let mut left_bbox = Aabb::empty();
+        for i in 1..n {
+            left_bbox = Aabb::enclosing(&left_bbox, &objects[i - 1].bounding_box());
+            let cost = left_bbox.surface_area() * i as f64 + right_areas[i] * (n - i) as f64;
+            if cost < best_cost {
+                best_cost = cost;
+                best_split = i;
+            }
+        }
+
+        best_split
+    }
+}
+
+impl From<HittableList> for BvhNode {
+    fn from(list: HittableList) -> Self {
+        Self::new(list)
+    }
+}
+
+impl Hittable for BvhNode {
+    fn hit<'a>(&'a self, r: &Ray, interval: Interval, rec: &mut HitRecord<'a>) -> bool {
+        let mut hit_anything = false;
+        let mut closest = interval.max();
+
+        if self.bbox.hit(r, interval.clone()) {
+            if let Some(left) = &self.left {
+                if left.hit(r, interval.clone(), rec) {
+                    hit_anything = true;
+                    closest = rec.t;
+                }
+            }
+            if let Some(right) = &self.right {
+                if right.hit(r, Interval::new(interval.min(), closest), rec) {
+                    hit_anything = true;
+                    closest = rec.t;
+                }
+            }
+        }
+
+        for object in &self.unbounded {
+            if object.hit(r, Interval::new(interval.min(), closest), rec) {
+                hit_anything = true;
+                closest = rec.t;
+            }
+        }
+
+        hit_anything
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.unbounded
+            .iter()
+            .fold(self.bbox.clone(), |acc, object| {
+                Aabb::enclosing(&acc, &object.bounding_box())
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    use crate::{
+        material::MaterialEnum,
+        plane::Plane,
+        ray::Point3,
+        sphere::Sphere,
+        utils::{random_double_in_range, RenderRng},
+        vec3::Vec3,
+    };
+
+    #[test]
+    fn test_matches_linear_scan() {
+        let mut rng = RenderRng::seed_from_u64(42);
+        let spheres: Vec<(Point3, f64)> = (0..200)
+            .map(|_| {
+                (
+                    Vec3::random_in_range(&mut rng, -10.0, 10.0),
+                    random_double_in_range(&mut rng, 0.1, 1.0),
+                )
+            })
+            .collect();
+
+        let mut linear = HittableList::new();
+        let mut bvh_list = HittableList::new();
+        for (center, radius) in spheres {
+            linear.add(Box::new(Sphere::new(
+                center,
+                radius,
+                MaterialEnum::default(),
+            )));
+            bvh_list.add(Box::new(Sphere::new(
+                center,
+                radius,
+                MaterialEnum::default(),
+            )));
+        }
+        let bvh = BvhNode::new(bvh_list);
+
+        for _ in 0..500 {
+            let r = Ray::new(
+                Point3::new(0.0, 0.0, 30.0),
+                Vec3::random_in_range(&mut rng, -1.0, 1.0),
+            );
+            let mut linear_rec = HitRecord::default();
+            let mut bvh_rec = HitRecord::default();
+
+            let linear_hit = linear.hit(&r, Interval::new(0.001, f64::INFINITY), &mut linear_rec);
+            let bvh_hit = bvh.hit(&r, Interval::new(0.001, f64::INFINITY), &mut bvh_rec);
+
+            assert_eq!(linear_hit, bvh_hit);
+            if linear_hit {
+                assert!((linear_rec.t - bvh_rec.t).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_unbounded_objects_sit_beside_the_tree() {
+        let mut list = HittableList::new();
+        for k in 0..8 {
+            list.add(Box::new(Sphere::new(
+                Point3::new(k as f64 * 3.0, 2.0, 0.0),
+                1.0,
+                MaterialEnum::default(),
+            )));
+        }
+        list.add(Box::new(Plane::new(
+            Point3::default(),
+            Vec3::new(0.0, 1.0, 0.0),
+            MaterialEnum::default(),
+        )));
+        let bvh = BvhNode::new(list);
+        assert!(!bvh.bounding_box().is_bounded());
+
+        // Straight down through a sphere: the sphere is nearer than the plane.
+        let r = Ray::new(Point3::new(6.0, 10.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let mut rec = HitRecord::default();
+        assert!(bvh.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+        assert!((rec.t - 7.0).abs() < 1e-9);
+
+        // Between the spheres, and from outside the tree's bounds, only the plane is hit.
+        for origin in [Point3::new(4.5, 10.0, 0.0), Point3::new(-50.0, 10.0, 40.0)] {
+            let r = Ray::new(origin, Vec3::new(0.0, -1.0, 0.0));
+            let mut rec = HitRecord::default();
+            assert!(bvh.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+            assert!((rec.t - 10.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_empty_list_never_hits() {
+        let bvh = BvhNode::new(HittableList::new());
+        let r = Ray::new(Point3::default(), Vec3::new(1.0, 0.0, 0.0));
+        let mut rec = HitRecord::default();
+        assert!(!bvh.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+    }
+}
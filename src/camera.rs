@@ -1,30 +1,235 @@
 use std::{
-    sync::{Arc, Mutex},
-    thread::{spawn, JoinHandle},
-    vec,
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
 };
 
 use crate::{
+    background::Background,
     colour::Colour,
-    hittable_list::HittableList,
+    framebuffer::{Framebuffer, SharedFramebuffer},
+    hittable::Hittable,
+    integrator::{Integrator, PathTracer},
     ray::{Point3, Ray},
-    utils::{degrees_to_radians, sample_square},
-    vec3::Vec3,
+    sampler::{Sampler, SamplerKind},
+    utils::degrees_to_radians,
+    vec3::{concentric_disk, Vec3},
 };
 
+/// Stops sampling a pixel once the standard error of its mean luminance falls to
+/// `threshold` times that mean. Every pixel takes at least `min_samples`, and
+/// `Camera::samples_per_pixel` becomes the most any pixel may take.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AdaptiveSampling {
+    pub threshold: f64,
+    pub min_samples: i64,
+}
+
+impl AdaptiveSampling {
+    pub const DEFAULT_MIN_SAMPLES: i64 = 16;
+
+    pub fn new(threshold: f64, min_samples: i64) -> Self {
+        Self {
+            threshold,
+            min_samples,
+        }
+    }
+}
+
+/// Running estimate of one pixel, tracking the variance of its luminance with
+/// Welford's algorithm.
+#[derive(Default)]
+struct PixelEstimate {
+    count: u32,
+    sum: Colour,
+    mean_luminance: f64,
+    m2: f64,
+}
+
+impl PixelEstimate {
+    /// Luminance below which the error is judged against this floor instead, so
+    /// near-black pixels are not sampled to the cap chasing a tiny relative error.
+    const MIN_LUMINANCE: f64 = 1e-2;
+
+    fn add(&mut self, sample: Colour) {
+        self.count += 1;
+        self.sum += sample;
+
+        let luminance = sample.luminance();
+        let delta = luminance - self.mean_luminance;
+        self.mean_luminance += delta / self.count as f64;
+        self.m2 += delta * (luminance - self.mean_luminance);
+    }
+
+    fn mean(&self) -> Colour {
+        match self.count {
+            0 => Colour::default(),
+            n => self.sum / n as f64,
+        }
+    }
+
+    fn converged(&self, adaptive: &AdaptiveSampling) -> bool {
+        if (self.count as i64) < adaptive.min_samples.max(2) {
+            return false;
+        }
+
+        let n = self.count as f64;
+        let standard_error = (self.m2 / (n - 1.0) / n).sqrt();
+        standard_error <= adaptive.threshold * self.mean_luminance.max(Self::MIN_LUMINANCE)
+    }
+}
+
+/// Side length in pixels of the square tiles that render threads take in turn.
+const TILE_SIZE: usize = 16;
+
+/// A rectangle of pixels, clipped to the image, rendered by one thread.
+struct Tile {
+    x: Range<usize>,
+    y: Range<usize>,
+}
+
+impl Tile {
+    /// Covers the image with tiles in row-major order.
+    fn grid(width: usize, height: usize) -> Vec<Tile> {
+        (0..height)
+            .step_by(TILE_SIZE)
+            .flat_map(|y| {
+                (0..width).step_by(TILE_SIZE).map(move |x| Tile {
+                    x: x..(x + TILE_SIZE).min(width),
+                    y: y..(y + TILE_SIZE).min(height),
+                })
+            })
+            .collect()
+    }
+
+    fn pixels(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.y
+            .clone()
+            .flat_map(move |j| self.x.clone().map(move |i| (i, j)))
+    }
+}
+
+/// A render in progress: the camera, the scene it is looking at and the tiles the
+/// image is divided into.
+struct RenderJob<'a> {
+    camera: &'a Camera,
+    world: &'a dyn Hittable,
+    lights: &'a dyn Hittable,
+    integrator: &'a dyn Integrator,
+    seed: u64,
+    tiles: Vec<Tile>,
+}
+
+impl<'a> RenderJob<'a> {
+    fn new(
+        camera: &'a Camera,
+        world: &'a dyn Hittable,
+        lights: &'a dyn Hittable,
+        integrator: &'a dyn Integrator,
+    ) -> Self {
+        Self {
+            camera,
+            world,
+            lights,
+            integrator,
+            // Every pixel draws from its own stream of this seed, so the image does
+            // not depend on which thread renders which tile.
+            seed: camera.seed.unwrap_or_else(rand::random),
+            tiles: Tile::grid(camera.image_width as usize, camera.image_height as usize),
+        }
+    }
+
+    /// Calls `render_tile` with each tile and its index. Threads take the next tile as
+    /// they finish one, so expensive regions of the image are shared out instead of
+    /// holding up whoever was given them.
+    fn for_each_tile(&self, render_tile: impl Fn(usize, &Tile, &mut Sampler) + Sync) {
+        let next_tile = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for _ in 0..self.camera.num_threads.max(1) {
+                scope.spawn(|| {
+                    let mut sampler = Sampler::new(
+                        self.camera.sampler,
+                        self.camera.samples_per_pixel,
+                        self.seed,
+                    );
+                    loop {
+                        let index = next_tile.fetch_add(1, Ordering::Relaxed);
+                        let Some(tile) = self.tiles.get(index) else {
+                            break;
+                        };
+                        render_tile(index, tile, &mut sampler);
+                    }
+                });
+            }
+        });
+    }
+
+    /// Adds the given samples of pixel (`i`, `j`) to `estimate`, stopping early once
+    /// it has converged under adaptive sampling.
+    fn sample_pixel(
+        &self,
+        i: usize,
+        j: usize,
+        samples: Range<i64>,
+        estimate: &mut PixelEstimate,
+        sampler: &mut Sampler,
+    ) {
+        let camera = self.camera;
+        for s in samples {
+            if camera
+                .adaptive
+                .is_some_and(|adaptive| estimate.converged(&adaptive))
+            {
+                break;
+            }
+
+            sampler.start_pixel_sample(i, j, s as usize);
+            let r = camera.get_ray(i as i64, j as i64, sampler);
+            estimate.add(self.integrator.radiance(
+                &r,
+                self.world,
+                self.lights,
+                &camera.background,
+                sampler,
+            ));
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Camera {
     pub aspect_ratio: f64,
     pub image_width: i64,
+    /// Exact image height in pixels, taking precedence over `aspect_ratio`; when unset
+    /// the height is derived from `image_width` and `aspect_ratio`.
+    pub requested_height: Option<i64>,
     pub samples_per_pixel: i64,
+    /// Bounce limit for the integrator built by [`Camera::path_tracer`].
     pub max_depth: i64,
+    /// Bounce count from which paths built by [`Camera::path_tracer`] are subject to
+    /// Russian roulette.
+    pub russian_roulette_depth: i64,
     pub vfov: i64,
     pub look_from: Point3,
     pub look_at: Point3,
     pub vup: Vec3,
     pub defocus_angle: f64,
     pub focus_dist: f64,
-    pixel_samples_scale: f64,
+    pub background: Background,
+    pub num_threads: usize,
+    /// Seed for the per-pixel sample streams; each render draws a fresh one when unset.
+    pub seed: Option<u64>,
+    /// How the pixel, lens, time and scattering dimensions of each sample are generated.
+    pub sampler: SamplerKind,
+    /// Per-pixel early termination; every pixel takes `samples_per_pixel` when unset.
+    pub adaptive: Option<AdaptiveSampling>,
+    /// Shutter interval, within the [0, 1] time range that object motion spans.
+    pub shutter_open: f64,
+    pub shutter_close: f64,
     image_height: i64,
     center: Point3,
     pixel00_loc: Point3,
@@ -35,6 +240,7 @@ pub struct Camera {
 }
 
 impl Camera {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         aspect_ratio: f64,
         image_width: i64,
@@ -47,153 +253,406 @@ impl Camera {
         defocus_angle: f64,
         focus_dist: f64,
     ) -> Self {
-        let mut image_height = (image_width as f64 / aspect_ratio) as i64;
-
-        if image_height < 1 {
-            image_height = 1
-        }
+        let mut camera = Self {
+            aspect_ratio,
+            image_width,
+            requested_height: None,
+            samples_per_pixel,
+            max_depth,
+            russian_roulette_depth: 3,
+            vfov,
+            look_from,
+            look_at,
+            vup,
+            defocus_angle,
+            focus_dist,
+            background: Background::default(),
+            num_threads: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+            seed: None,
+            sampler: SamplerKind::default(),
+            adaptive: None,
+            shutter_open: 0.0,
+            shutter_close: 0.0,
+            image_height: 0,
+            center: Point3::default(),
+            pixel00_loc: Point3::default(),
+            pixel_delta_u: Vec3::default(),
+            pixel_delta_v: Vec3::default(),
+            defocus_disk_u: Vec3::default(),
+            defocus_disk_v: Vec3::default(),
+        };
+        camera.initialize();
+        camera
+    }
 
-        let pixel_samples_scale = 1.0 / samples_per_pixel as f64;
+    /// Recomputes the derived viewport state; call after changing any of the public fields.
+    pub fn initialize(&mut self) {
+        self.image_height = self
+            .requested_height
+            .unwrap_or((self.image_width as f64 / self.aspect_ratio) as i64)
+            .max(1);
 
-        let center = look_from;
+        self.center = self.look_from;
 
-        let theta = degrees_to_radians(vfov as f64);
+        let theta = degrees_to_radians(self.vfov as f64);
         let h = (theta / 2.0).tan();
-        let viewport_height = 2.0 * h * focus_dist;
-        let viewport_width = viewport_height * (image_width as f64 / image_height as f64);
+        let viewport_height = 2.0 * h * self.focus_dist;
+        let viewport_width = viewport_height * (self.image_width as f64 / self.image_height as f64);
 
-        let w = (look_from - look_at).unit_vector();
-        let u = Vec3::cross(vup, w).unit_vector();
+        let w = (self.look_from - self.look_at).unit_vector();
+        let u = Vec3::cross(self.vup, w).unit_vector();
         let v = Vec3::cross(w, u);
 
         let viewport_u = viewport_width * u;
         let viewport_v = viewport_height * -v;
 
-        let pixel_delta_u = viewport_u / image_width as f64;
-        let pixel_delta_v = viewport_v / image_height as f64;
+        self.pixel_delta_u = viewport_u / self.image_width as f64;
+        self.pixel_delta_v = viewport_v / self.image_height as f64;
 
-        let viewport_upper_left = center - (focus_dist * w) - viewport_u / 2.0 - viewport_v / 2.0;
+        let viewport_upper_left =
+            self.center - (self.focus_dist * w) - viewport_u / 2.0 - viewport_v / 2.0;
 
-        let pixel00_loc = viewport_upper_left + 0.5 * (pixel_delta_u + pixel_delta_v);
+        self.pixel00_loc = viewport_upper_left + 0.5 * (self.pixel_delta_u + self.pixel_delta_v);
 
-        let defocus_radius = focus_dist * degrees_to_radians(defocus_angle / 2.0).tan();
-        let defocus_disk_u = u * defocus_radius;
-        let defocus_disk_v = v * defocus_radius;
+        let defocus_radius = self.focus_dist * degrees_to_radians(self.defocus_angle / 2.0).tan();
+        self.defocus_disk_u = u * defocus_radius;
+        self.defocus_disk_v = v * defocus_radius;
+    }
 
-        Self {
-            aspect_ratio,
-            image_width,
-            samples_per_pixel,
-            max_depth,
-            vfov,
-            look_from,
-            look_at,
-            vup,
-            defocus_angle,
-            focus_dist,
-            pixel_samples_scale,
-            image_height,
-            center,
-            pixel00_loc,
-            pixel_delta_u,
-            pixel_delta_v,
-            defocus_disk_u,
-            defocus_disk_v,
-        }
+    pub fn image_height(&self) -> i64 {
+        self.image_height
     }
 
-    pub fn render(&self, world: Arc<HittableList>) {
-        let num_threads = std::thread::available_parallelism().unwrap().get();
-        let chunk_size = self.image_height as usize / num_threads;
-
-        let results = Arc::new(Mutex::new(vec![String::new(); self.image_height as usize]));
-
-        (0..num_threads)
-            .map(|t| {
-                let start = t * chunk_size;
-                let end = match t == num_threads - 1 {
-                    true => self.image_height as usize,
-                    false => (t + 1) * chunk_size,
-                };
-
-                let results_clone = Arc::clone(&results);
-                let world_clone = Arc::clone(&world);
-                let self_clone = self.clone();
-                spawn(move || {
-                    Camera::render_chunk(
-                        t,
-                        self_clone,
-                        start,
-                        end,
-                        world_clone,
-                        results_clone,
-                        t == num_threads - 1,
-                    );
-                })
-            })
-            .collect::<Vec<JoinHandle<()>>>()
-            .into_iter()
-            .for_each(|handle| handle.join().unwrap());
+    /// The default integrator: a path tracer limited by this camera's `max_depth` and
+    /// `russian_roulette_depth`.
+    pub fn path_tracer(&self) -> PathTracer {
+        PathTracer::new(self.max_depth, self.russian_roulette_depth)
+    }
 
-        print!("P3\n{} {}\n255\n", self.image_width, self.image_height);
+    /// Renders `world` with `integrator`, which may sample the emitters in `lights`
+    /// directly. An empty light list leaves light to be found by random bounces.
+    /// `on_tile` is called from the render threads with the number of tiles finished
+    /// so far and the total, as each tile completes.
+    pub fn render(
+        &self,
+        world: Arc<dyn Hittable>,
+        lights: Arc<dyn Hittable>,
+        integrator: Arc<dyn Integrator>,
+        on_tile: impl Fn(usize, usize) + Sync,
+    ) -> Framebuffer {
+        let job = RenderJob::new(self, world.as_ref(), lights.as_ref(), integrator.as_ref());
+        let results = SharedFramebuffer::new(self.image_width as usize, self.image_height as usize);
+        let finished_tiles = AtomicUsize::new(0);
 
-        let results = Arc::try_unwrap(results).unwrap().into_inner().unwrap();
-        for line in results {
-            print!("{}", line);
-        }
+        job.for_each_tile(|_, tile, sampler| {
+            for (i, j) in tile.pixels() {
+                let mut estimate = PixelEstimate::default();
+                job.sample_pixel(i, j, 0..self.samples_per_pixel, &mut estimate, sampler);
+                results.set(i, j, estimate.mean(), estimate.count);
+            }
+
+            let finished = finished_tiles.fetch_add(1, Ordering::Relaxed) + 1;
+            on_tile(finished, job.tiles.len());
+        });
 
-        eprintln!("");
+        results.to_framebuffer()
     }
 
-    fn render_chunk(
-        chunk: usize,
-        camera: Camera,
-        start: usize,
-        end: usize,
-        world: Arc<HittableList>,
-        results: Arc<Mutex<Vec<String>>>,
-        report: bool,
-    ) {
-        for j in start..end {
-            if report {
-                eprint!(
-                    "\rChunk {} working on line {} of {}",
-                    { chunk },
-                    { j - start },
-                    { end - start }
-                );
+    /// Renders like [`Camera::render`], but one sample per pixel at a time, handing
+    /// `on_pass` the number of passes so far and the image averaged over them after
+    /// each one. Without a `time_budget` this runs `samples_per_pixel` passes and,
+    /// given the same seed, matches [`Camera::render`]. With one, the budget replaces
+    /// the sample count: passes continue until it has elapsed, finishing the current
+    /// pass. Either way the render ends early once adaptive sampling has stopped every
+    /// pixel.
+    pub fn render_progressive(
+        &self,
+        world: Arc<dyn Hittable>,
+        lights: Arc<dyn Hittable>,
+        integrator: Arc<dyn Integrator>,
+        time_budget: Option<Duration>,
+        on_pass: impl FnMut(usize, &Framebuffer),
+    ) -> Framebuffer {
+        let start_time = Instant::now();
+        self.render_passes(
+            world,
+            lights,
+            integrator,
+            time_budget,
+            || start_time.elapsed(),
+            on_pass,
+        )
+    }
+
+    /// [`Camera::render_progressive`] measuring the budget against `elapsed`.
+    fn render_passes(
+        &self,
+        world: Arc<dyn Hittable>,
+        lights: Arc<dyn Hittable>,
+        integrator: Arc<dyn Integrator>,
+        time_budget: Option<Duration>,
+        elapsed: impl Fn() -> Duration,
+        mut on_pass: impl FnMut(usize, &Framebuffer),
+    ) -> Framebuffer {
+        let job = RenderJob::new(self, world.as_ref(), lights.as_ref(), integrator.as_ref());
+        let results = SharedFramebuffer::new(self.image_width as usize, self.image_height as usize);
+
+        // Running estimates for each tile's pixels. Only one thread works on a tile
+        // in any pass, so these locks are never contended.
+        let accumulation: Vec<Mutex<Vec<PixelEstimate>>> = job
+            .tiles
+            .iter()
+            .map(|tile| Mutex::new(tile.pixels().map(|_| PixelEstimate::default()).collect()))
+            .collect();
+
+        let mut image = results.to_framebuffer();
+        for pass in 0.. {
+            let finished = match time_budget {
+                Some(budget) => pass > 0 && elapsed() >= budget,
+                None => pass >= self.samples_per_pixel,
+            };
+            if finished {
+                break;
             }
-            let mut line_result = String::new();
-            for i in 0..camera.image_width {
-                let mut pixel_colour = Colour::new(0.0, 0.0, 0.0);
-                for _ in 0..camera.samples_per_pixel {
-                    let r = Camera::get_ray(&camera, i, j as i64);
-                    pixel_colour += r.colour(&world, camera.max_depth);
+
+            let sampled = AtomicBool::new(false);
+            job.for_each_tile(|index, tile, sampler| {
+                let mut estimates = accumulation[index].lock().unwrap();
+                for ((i, j), estimate) in tile.pixels().zip(estimates.iter_mut()) {
+                    let count = estimate.count;
+                    job.sample_pixel(i, j, pass..pass + 1, estimate, sampler);
+                    if estimate.count > count {
+                        sampled.store(true, Ordering::Relaxed);
+                        results.set(i, j, estimate.mean(), estimate.count);
+                    }
                 }
-                pixel_colour *= camera.pixel_samples_scale;
-                line_result.push_str(&pixel_colour.write());
+            });
+            if !sampled.into_inner() {
+                break;
             }
-            results.lock().unwrap()[j] = line_result;
+
+            image = results.to_framebuffer();
+            on_pass(pass as usize + 1, &image);
         }
+
+        image
     }
 
-    fn get_ray(&self, i: i64, j: i64) -> Ray {
-        let offset = sample_square();
+    /// Camera ray for the current sample of pixel (`i`, `j`). The pixel offset, lens
+    /// position and shutter time always take the sampler's first five dimensions.
+    fn get_ray(&self, i: i64, j: i64, sampler: &mut Sampler) -> Ray {
+        let (dx, dy) = sampler.get_2d();
+        let lens_sample = sampler.get_2d();
+        let time_sample = sampler.get_1d();
+
         let pixel_sample = self.pixel00_loc
-            + ((i as f64 + offset.x()) * self.pixel_delta_u)
-            + ((j as f64 + offset.y()) * self.pixel_delta_v);
+            + ((i as f64 + dx - 0.5) * self.pixel_delta_u)
+            + ((j as f64 + dy - 0.5) * self.pixel_delta_v);
 
         let origin = match self.defocus_angle <= 0.0 {
             true => self.center,
-            false => self.defocus_disk_sample(),
+            false => self.defocus_disk_sample(lens_sample),
         };
         let direction = pixel_sample - origin;
+        let time =
+            self.shutter_open + time_sample * (self.shutter_close - self.shutter_open).max(0.0);
 
-        Ray::new(origin, direction)
+        Ray::with_time(origin, direction, time)
     }
 
-    fn defocus_disk_sample(&self) -> Point3 {
-        let p = Vec3::random_in_unit_disk();
+    fn defocus_disk_sample(&self, u: (f64, f64)) -> Point3 {
+        let p = concentric_disk(u);
         self.center + (p[0] * self.defocus_disk_u) + (p[1] * self.defocus_disk_v)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{
+        hittable_list::HittableList,
+        material::{Dielectric, DiffuseLight, Lambertian, MaterialEnum},
+        sphere::Sphere,
+    };
+
+    fn scene(num_threads: usize, seed: u64) -> (Camera, Arc<dyn Hittable>, Arc<dyn Hittable>) {
+        let mut camera = Camera::new(
+            2.0,
+            24,
+            4,
+            8,
+            40,
+            Point3::new(0.0, 1.0, 6.0),
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            1.0,
+            6.0,
+        );
+        camera.num_threads = num_threads;
+        camera.seed = Some(seed);
+        camera.russian_roulette_depth = 2;
+
+        let mut world = HittableList::new();
+        world.add(Box::new(Sphere::new(
+            Point3::new(0.0, -100.5, 0.0),
+            100.0,
+            MaterialEnum::Lambertian(Lambertian::new(Colour::new(0.5, 0.5, 0.5))),
+        )));
+        world.add(Box::new(Sphere::new(
+            Point3::new(0.0, 0.0, 0.0),
+            0.5,
+            MaterialEnum::Dielectric(Dielectric::new(1.5)),
+        )));
+        world.add(Box::new(Sphere::new(
+            Point3::new(0.0, 3.0, 0.0),
+            1.0,
+            MaterialEnum::DiffuseLight(DiffuseLight::new(Colour::new(4.0, 4.0, 4.0))),
+        )));
+        let mut lights = HittableList::new();
+        lights.add(Box::new(Sphere::new(
+            Point3::new(0.0, 3.0, 0.0),
+            1.0,
+            MaterialEnum::default(),
+        )));
+
+        (camera, Arc::new(world), Arc::new(lights))
+    }
+
+    fn render(num_threads: usize, seed: u64) -> Framebuffer {
+        let (camera, world, lights) = scene(num_threads, seed);
+        camera.render(world, lights, Arc::new(camera.path_tracer()), |_, _| {})
+    }
+
+    fn bits(image: &Framebuffer) -> Vec<[u64; 3]> {
+        image
+            .pixels()
+            .iter()
+            .map(|c| [c.x().to_bits(), c.y().to_bits(), c.z().to_bits()])
+            .collect()
+    }
+
+    #[test]
+    fn test_adaptive_sampling_stops_early_on_flat_pixels() {
+        let mut camera = Camera::new(
+            1.0,
+            8,
+            64,
+            4,
+            40,
+            Point3::new(0.0, 0.0, 6.0),
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            0.0,
+            6.0,
+        );
+        camera.num_threads = 2;
+        camera.seed = Some(3);
+        camera.background = Background::Solid(Colour::new(0.2, 0.4, 0.6));
+        let render = |camera: &Camera| {
+            camera.render(
+                Arc::new(HittableList::new()),
+                Arc::new(HittableList::new()),
+                Arc::new(camera.path_tracer()),
+                |_, _| {},
+            )
+        };
+
+        assert!(render(&camera).sample_counts().iter().all(|&n| n == 64));
+
+        camera.adaptive = Some(AdaptiveSampling::new(0.01, 8));
+        let image = render(&camera);
+        assert!(image.sample_counts().iter().all(|&n| n == 8));
+        assert!((image.get(4, 4) - Colour::new(0.2, 0.4, 0.6)).length() < 1e-12);
+    }
+
+    #[test]
+    fn test_requested_height_is_kept_exactly() {
+        let (mut camera, _, _) = scene(1, 0);
+        camera.image_width = 1920;
+        camera.requested_height = Some(113);
+        camera.initialize();
+        assert_eq!(camera.image_height(), 113);
+    }
+
+    #[test]
+    fn test_tiles_cover_each_pixel_once() {
+        let (width, height) = (37, 20);
+        let mut covered = vec![0; width * height];
+        for tile in Tile::grid(width, height) {
+            for (i, j) in tile.pixels() {
+                covered[j * width + i] += 1;
+            }
+        }
+        assert!(covered.iter().all(|&n| n == 1));
+    }
+
+    #[test]
+    fn test_seeded_render_ignores_thread_count() {
+        let single = render(1, 9);
+        let threaded = render(5, 9);
+        assert_eq!(bits(&single), bits(&threaded));
+        assert_ne!(bits(&single), bits(&render(1, 10)));
+    }
+
+    #[test]
+    fn test_progressive_render_converges_to_full_render() {
+        let (camera, world, lights) = scene(3, 9);
+        let mut passes = Vec::new();
+        let image = camera.render_progressive(
+            world,
+            lights,
+            Arc::new(camera.path_tracer()),
+            None,
+            |pass, image| passes.push((pass, image.sample_counts()[0])),
+        );
+
+        assert_eq!(passes, [(1, 1), (2, 2), (3, 3), (4, 4)]);
+        assert_eq!(bits(&image), bits(&render(1, 9)));
+    }
+
+    #[test]
+    fn test_progressive_time_budget_replaces_sample_count() {
+        let (mut camera, world, lights) = scene(2, 9);
+        camera.image_width = 4;
+        camera.initialize();
+        // Each reading of the clock advances it by 10 ms.
+        let readings = Cell::new(0);
+        let clock = || {
+            readings.set(readings.get() + 1);
+            Duration::from_millis(10 * readings.get())
+        };
+        let mut passes = 0;
+        let image = camera.render_passes(
+            world,
+            lights,
+            Arc::new(camera.path_tracer()),
+            Some(Duration::from_millis(100)),
+            clock,
+            |_, _| passes += 1,
+        );
+
+        assert_eq!(passes, 10);
+        assert!(passes > camera.samples_per_pixel as usize);
+        assert!(image.sample_counts().iter().all(|&n| n == 10));
+    }
+
+    #[test]
+    fn test_progressive_render_stops_at_time_budget() {
+        let (camera, world, lights) = scene(2, 9);
+        let mut passes = 0;
+        let image = camera.render_progressive(
+            world,
+            lights,
+            Arc::new(camera.path_tracer()),
+            Some(Duration::ZERO),
+            |_, _| passes += 1,
+        );
+
+        assert_eq!(passes, 1);
+        assert!(image.sample_counts().iter().all(|&n| n == 1));
+    }
+}
@@ -3,39 +3,48 @@ use crate::{interval::Interval, vec3};
 pub type Colour = vec3::Vec3;
 
 impl Colour {
-    pub fn write(&self) -> String {
+    pub fn to_rgb8(&self) -> [u8; 3] {
         let intensity = Interval::new(0.000, 0.999);
 
-        let r = linear_to_gamma(self.x());
-        let g = linear_to_gamma(self.y());
-        let b = linear_to_gamma(self.z());
+        let r = linear_to_srgb(self.x());
+        let g = linear_to_srgb(self.y());
+        let b = linear_to_srgb(self.z());
+
+        [
+            (255.999 * intensity.clamp(r)) as u8,
+            (255.999 * intensity.clamp(g)) as u8,
+            (255.999 * intensity.clamp(b)) as u8,
+        ]
+    }
 
-        let rbyte = (255.999 * intensity.clamp(r)) as i64;
-        let gbyte = (255.999 * intensity.clamp(g)) as i64;
-        let bbyte = (255.999 * intensity.clamp(b)) as i64;
+    /// Relative luminance of a linear Rec. 709 colour.
+    pub fn luminance(&self) -> f64 {
+        0.2126 * self.x() + 0.7152 * self.y() + 0.0722 * self.z()
+    }
 
+    pub fn write(&self) -> String {
+        let [rbyte, gbyte, bbyte] = self.to_rgb8();
         format!("{} {} {}\n", rbyte, gbyte, bbyte)
     }
 
     pub fn display(&self) {
-        let intensity = Interval::new(0.000, 0.999);
-
-        let r = linear_to_gamma(self.x());
-        let g = linear_to_gamma(self.y());
-        let b = linear_to_gamma(self.z());
-
-        let rbyte = (255.999 * intensity.clamp(r)) as i64;
-        let gbyte = (255.999 * intensity.clamp(g)) as i64;
-        let bbyte = (255.999 * intensity.clamp(b)) as i64;
-
-        print!("{} {} {}\n", rbyte, gbyte, bbyte)
+        print!("{}", self.write())
     }
 }
 
-fn linear_to_gamma(linear_component: f64) -> f64 {
-    if linear_component > 0.0 {
-        return linear_component.sqrt();
+/// Inverse of [`linear_to_srgb`], decoding an sRGB-encoded component in [0, 1].
+pub fn srgb_to_linear(srgb_component: f64) -> f64 {
+    match srgb_component {
+        x if x <= 0.0 => 0.0,
+        x if x <= 0.04045 => x / 12.92,
+        x => ((x + 0.055) / 1.055).powf(2.4),
     }
+}
 
-    0.0
+pub fn linear_to_srgb(linear_component: f64) -> f64 {
+    match linear_component {
+        x if x <= 0.0 => 0.0,
+        x if x <= 0.0031308 => 12.92 * x,
+        x => 1.055 * x.powf(1.0 / 2.4) - 0.055,
+    }
 }
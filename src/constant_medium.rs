@@ -0,0 +1,205 @@
+use crate::{
+    aabb::Aabb,
+    colour::Colour,
+    hittable::{HitRecord, Hittable},
+    interval::Interval,
+    material::{Isotropic, MaterialEnum},
+    ray::Ray,
+    texture::TextureEnum,
+    utils::{mix64, random_double, stream_rng},
+    vec3::Vec3,
+};
+
+/// A homogeneous participating medium filling a boundary shape. Rays travelling
+/// through it scatter after an exponentially distributed free-flight distance.
+///
+/// The boundary must be closed and convex: only the first entry and exit points
+/// along a ray are considered.
+pub struct ConstantMedium {
+    boundary: Box<dyn Hittable>,
+    neg_inv_density: f64,
+    phase_function: MaterialEnum,
+    /// Distinguishes this medium's free-flight samples from those of other media
+    /// crossed by the same ray, so their transmittances multiply.
+    identity: u64,
+}
+
+impl ConstantMedium {
+    pub fn new(boundary: Box<dyn Hittable>, density: f64, albedo: Colour) -> Self {
+        Self::from_texture(boundary, density, TextureEnum::from(albedo))
+    }
+
+    pub fn from_texture(boundary: Box<dyn Hittable>, density: f64, tex: TextureEnum) -> Self {
+        Self::with_phase_function(
+            boundary,
+            density,
+            MaterialEnum::Isotropic(Isotropic::from_texture(tex)),
+        )
+    }
+
+    pub fn with_phase_function(
+        boundary: Box<dyn Hittable>,
+        density: f64,
+        phase_function: MaterialEnum,
+    ) -> Self {
+        let bbox = boundary.bounding_box();
+        let identity = [
+            bbox.x.min(),
+            bbox.x.max(),
+            bbox.y.min(),
+            bbox.y.max(),
+            bbox.z.min(),
+            bbox.z.max(),
+            density,
+        ]
+        .iter()
+        .fold(0, |h, c| mix64(h ^ c.to_bits()));
+
+        Self {
+            boundary,
+            neg_inv_density: -1.0 / density,
+            phase_function,
+            identity,
+        }
+    }
+}
+
+impl Hittable for ConstantMedium {
+    fn hit<'a>(&'a self, r: &Ray, interval: Interval, rec: &mut HitRecord<'a>) -> bool {
+        let mut rec1 = HitRecord::default();
+        let mut rec2 = HitRecord::default();
+
+        if !self.boundary.hit(r, Interval::universe(), &mut rec1) {
+            return false;
+        }
+        if !self
+            .boundary
+            .hit(r, Interval::new(rec1.t + 0.0001, f64::INFINITY), &mut rec2)
+        {
+            return false;
+        }
+
+        let t_enter = rec1.t.max(interval.min());
+        let t_exit = rec2.t.min(interval.max());
+        if t_enter >= t_exit {
+            return false;
+        }
+        let t_enter = t_enter.max(0.0);
+
+        let ray_length = r.direction().length();
+        let distance_inside_boundary = (t_exit - t_enter) * ray_length;
+        let hit_distance = self.neg_inv_density * ray_uniform(r, self.identity).ln();
+
+        if hit_distance > distance_inside_boundary {
+            return false;
+        }
+
+        rec.t = t_enter + hit_distance / ray_length;
+        rec.p = r.at(rec.t);
+        // The phase function ignores these, but they must hold something sane.
+        rec.normal = Vec3::new(1.0, 0.0, 0.0);
+        rec.front_face = true;
+        rec.material = &self.phase_function;
+
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.boundary.bounding_box()
+    }
+}
+
+/// A uniform number derived from the ray itself and drawn from the `stream` of one
+/// medium. `Hittable::hit` takes no generator, and since rays come from seeded
+/// sampling this keeps free flights reproducible.
+fn ray_uniform(r: &Ray, stream: u64) -> f64 {
+    let (o, d) = (r.origin(), r.direction());
+    let seed = [o.x(), o.y(), o.z(), d.x(), d.y(), d.z(), r.time()]
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325, |h: u64, c| {
+            (h ^ c.to_bits()).wrapping_mul(0x100_0000_01b3)
+        });
+    random_double(&mut stream_rng(seed, stream))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    use crate::{
+        quad::make_box,
+        ray::Point3,
+        sphere::Sphere,
+        utils::{random_double_in_range, RenderRng},
+    };
+
+    fn fog(density: f64) -> ConstantMedium {
+        let boundary = Sphere::new(Point3::default(), 1.0, MaterialEnum::default());
+        ConstantMedium::new(Box::new(boundary), density, Colour::new(1.0, 1.0, 1.0))
+    }
+
+    #[test]
+    fn test_dense_medium_scatters_near_entry() {
+        let medium = fog(1e9);
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let mut rec = HitRecord::default();
+        assert!(medium.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+        assert!((rec.t - 4.0).abs() < 1e-6);
+        assert!(matches!(rec.material, MaterialEnum::Isotropic(_)));
+    }
+
+    #[test]
+    fn test_thin_medium_lets_rays_through() {
+        let medium = fog(1e-12);
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let mut rec = HitRecord::default();
+        assert!(!medium.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+    }
+
+    #[test]
+    fn test_ray_starting_inside_scatters_ahead_of_origin() {
+        let medium = fog(1e9);
+        let r = Ray::new(Point3::default(), Vec3::new(0.0, 0.0, 2.0));
+        let mut rec = HitRecord::default();
+        assert!(medium.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+        assert!((rec.t - 0.001).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_media_on_one_ray_attenuate_independently() {
+        let slab = |z: f64| {
+            let boundary = make_box(
+                Point3::new(-1.0, -1.0, z),
+                Point3::new(1.0, 1.0, z + 1.0),
+                MaterialEnum::default(),
+            );
+            ConstantMedium::new(Box::new(boundary), 0.7, Colour::new(1.0, 1.0, 1.0))
+        };
+        let (near, far) = (slab(0.0), slab(2.0));
+
+        let mut rng = RenderRng::seed_from_u64(5);
+        let n = 20_000;
+        let mut through = 0;
+        for _ in 0..n {
+            let origin = Point3::new(
+                random_double_in_range(&mut rng, -0.9, 0.9),
+                random_double_in_range(&mut rng, -0.9, 0.9),
+                -5.0,
+            );
+            let r = Ray::new(origin, Vec3::new(0.0, 0.0, 1.0));
+            let mut rec = HitRecord::default();
+            let interval = Interval::new(0.001, f64::INFINITY);
+            if !near.hit(&r, interval.clone(), &mut rec) && !far.hit(&r, interval, &mut rec) {
+                through += 1;
+            }
+        }
+
+        let transmittance = through as f64 / n as f64;
+        assert!(
+            (transmittance - (-1.4f64).exp()).abs() < 0.015,
+            "transmittance {}",
+            transmittance
+        );
+    }
+}
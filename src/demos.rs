@@ -0,0 +1,471 @@
+use std::sync::Arc;
+
+use crate::{
+    background::Background,
+    camera::Camera,
+    colour::Colour,
+    constant_medium::ConstantMedium,
+    hittable::Hittable,
+    hittable_list::HittableList,
+    material::{Dielectric, DiffuseLight, Lambertian, MaterialEnum, Metal},
+    quad::{make_box, Quad},
+    ray::Point3,
+    scene::Scene,
+    sphere::Sphere,
+    texture::{CheckerTexture, NoiseStyle, NoiseTexture, TextureEnum},
+    transform::{Instance, Transform},
+    utils::{random_double, random_double_in_range, RenderRng},
+    vec3::Vec3,
+};
+
+pub const DEMO_SCENES: &[&str] = &[
+    "final",
+    "bouncing-spheres",
+    "checkered-spheres",
+    "perlin-spheres",
+    "simple-light",
+    "quads",
+    "cornell-box",
+    "cornell-smoke",
+];
+
+/// Builds the named demo; `rng` drives the layout of the randomly generated scenes.
+pub fn demo_scene(name: &str, rng: &mut RenderRng) -> Option<Scene> {
+    match name {
+        "final" => Some(final_scene(rng)),
+        "bouncing-spheres" => Some(bouncing_spheres(rng)),
+        "checkered-spheres" => Some(checkered_spheres()),
+        "perlin-spheres" => Some(perlin_spheres()),
+        "simple-light" => Some(simple_light()),
+        "quads" => Some(quads()),
+        "cornell-box" => Some(cornell_box()),
+        "cornell-smoke" => Some(cornell_smoke()),
+        _ => None,
+    }
+}
+
+pub fn final_scene(rng: &mut RenderRng) -> Scene {
+    random_spheres(false, rng)
+}
+
+/// The final scene with the diffuse spheres bouncing during a unit shutter interval.
+pub fn bouncing_spheres(rng: &mut RenderRng) -> Scene {
+    random_spheres(true, rng)
+}
+
+fn random_spheres(bouncing: bool, rng: &mut RenderRng) -> Scene {
+    let mut camera = Camera::new(
+        16.0 / 9.0,
+        1200,
+        500,
+        50,
+        20,
+        Point3::new(13.0, 2.0, 3.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        0.6,
+        10.0,
+    );
+    if bouncing {
+        camera.shutter_close = 1.0;
+    }
+
+    let mut world = HittableList::new();
+
+    let checker =
+        CheckerTexture::from_colours(0.32, Colour::new(0.2, 0.3, 0.1), Colour::new(0.9, 0.9, 0.9));
+    let ground = Lambertian::from_texture(TextureEnum::Checker(checker));
+    world.add(Box::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        MaterialEnum::Lambertian(ground),
+    )));
+
+    for a in -11..11 {
+        for b in -11..11 {
+            let choose_mat = random_double(rng);
+            let center = Point3::new(
+                a as f64 + 0.9 * random_double(rng),
+                0.2,
+                b as f64 + 0.9 * random_double(rng),
+            );
+
+            if (center - Point3::new(4.0, 0.2, 0.0)).length() > 0.9 {
+                if choose_mat < 0.8 {
+                    let albedo = Colour::random(rng) * Colour::random(rng);
+                    let sphere_material = MaterialEnum::Lambertian(Lambertian::new(albedo));
+                    match bouncing {
+                        true => {
+                            let center2 =
+                                center + Vec3::new(0.0, random_double_in_range(rng, 0.0, 0.5), 0.0);
+                            world.add(Box::new(Sphere::moving(
+                                center,
+                                center2,
+                                0.2,
+                                sphere_material,
+                            )));
+                        }
+                        false => {
+                            world.add(Box::new(Sphere::new(center, 0.2, sphere_material)));
+                        }
+                    }
+                } else if choose_mat < 0.95 {
+                    let albedo = Colour::random_in_range(rng, 0.5, 1.0);
+                    let fuzz = random_double_in_range(rng, 0.0, 0.5);
+                    let sphere_material = Metal::new(albedo, fuzz);
+                    world.add(Box::new(Sphere::new(
+                        center,
+                        0.2,
+                        MaterialEnum::Metal(sphere_material),
+                    )));
+                } else {
+                    let sphere_material = Dielectric::new(1.5);
+                    world.add(Box::new(Sphere::new(
+                        center,
+                        0.2,
+                        MaterialEnum::Dielectric(sphere_material),
+                    )));
+                }
+            }
+        }
+    }
+
+    let material1 = Dielectric::new(1.5);
+    world.add(Box::new(Sphere::new(
+        Point3::new(0.0, 1.0, 0.0),
+        1.0,
+        MaterialEnum::Dielectric(material1),
+    )));
+
+    let material2 = Lambertian::new(Colour::new(0.4, 0.2, 0.1));
+    world.add(Box::new(Sphere::new(
+        Point3::new(-4.0, 1.0, 0.0),
+        1.0,
+        MaterialEnum::Lambertian(material2),
+    )));
+
+    let material3 = Metal::new(Colour::new(0.7, 0.6, 0.5), 0.0);
+    world.add(Box::new(Sphere::new(
+        Point3::new(4.0, 1.0, 0.0),
+        1.0,
+        MaterialEnum::Metal(material3),
+    )));
+
+    Scene {
+        camera,
+        world,
+        lights: HittableList::new(),
+        diagnostics: Vec::new(),
+    }
+}
+
+pub fn checkered_spheres() -> Scene {
+    let camera = Camera::new(
+        16.0 / 9.0,
+        400,
+        100,
+        50,
+        20,
+        Point3::new(13.0, 2.0, 3.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        0.0,
+        10.0,
+    );
+
+    let mut world = HittableList::new();
+
+    let checker = TextureEnum::Checker(CheckerTexture::from_colours(
+        0.32,
+        Colour::new(0.2, 0.3, 0.1),
+        Colour::new(0.9, 0.9, 0.9),
+    ));
+    for y in [-10.0, 10.0] {
+        world.add(Box::new(Sphere::new(
+            Point3::new(0.0, y, 0.0),
+            10.0,
+            MaterialEnum::Lambertian(Lambertian::from_texture(checker.clone())),
+        )));
+    }
+
+    Scene {
+        camera,
+        world,
+        lights: HittableList::new(),
+        diagnostics: Vec::new(),
+    }
+}
+
+pub fn perlin_spheres() -> Scene {
+    let camera = Camera::new(
+        16.0 / 9.0,
+        400,
+        100,
+        50,
+        20,
+        Point3::new(13.0, 2.0, 3.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        0.0,
+        10.0,
+    );
+
+    let mut world = HittableList::new();
+
+    let marble = TextureEnum::Noise(NoiseTexture::new(NoiseStyle::Marble, 4.0));
+    world.add(Box::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        MaterialEnum::Lambertian(Lambertian::from_texture(marble.clone())),
+    )));
+    world.add(Box::new(Sphere::new(
+        Point3::new(0.0, 2.0, 0.0),
+        2.0,
+        MaterialEnum::Lambertian(Lambertian::from_texture(marble)),
+    )));
+
+    Scene {
+        camera,
+        world,
+        lights: HittableList::new(),
+        diagnostics: Vec::new(),
+    }
+}
+
+pub fn simple_light() -> Scene {
+    let mut camera = Camera::new(
+        16.0 / 9.0,
+        400,
+        100,
+        50,
+        20,
+        Point3::new(26.0, 3.0, 6.0),
+        Point3::new(0.0, 2.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        0.0,
+        10.0,
+    );
+    camera.background = Background::Black;
+
+    let mut world = HittableList::new();
+
+    let marble = TextureEnum::Noise(NoiseTexture::new(NoiseStyle::Marble, 4.0));
+    world.add(Box::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        MaterialEnum::Lambertian(Lambertian::from_texture(marble.clone())),
+    )));
+    world.add(Box::new(Sphere::new(
+        Point3::new(0.0, 2.0, 0.0),
+        2.0,
+        MaterialEnum::Lambertian(Lambertian::from_texture(marble)),
+    )));
+
+    let light = DiffuseLight::new(Colour::new(4.0, 4.0, 4.0));
+    world.add(Box::new(Sphere::new(
+        Point3::new(0.0, 7.0, 0.0),
+        2.0,
+        MaterialEnum::DiffuseLight(light),
+    )));
+
+    let mut lights = HittableList::new();
+    lights.add(Box::new(Sphere::new(
+        Point3::new(0.0, 7.0, 0.0),
+        2.0,
+        MaterialEnum::default(),
+    )));
+
+    Scene {
+        camera,
+        world,
+        lights,
+        diagnostics: Vec::new(),
+    }
+}
+
+pub fn quads() -> Scene {
+    let camera = Camera::new(
+        1.0,
+        400,
+        100,
+        50,
+        80,
+        Point3::new(0.0, 0.0, 9.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        0.0,
+        10.0,
+    );
+
+    let mut world = HittableList::new();
+
+    let sides = [
+        (
+            Colour::new(1.0, 0.2, 0.2),
+            Point3::new(-3.0, -2.0, 5.0),
+            Vec3::new(0.0, 0.0, -4.0),
+            Vec3::new(0.0, 4.0, 0.0),
+        ),
+        (
+            Colour::new(0.2, 1.0, 0.2),
+            Point3::new(-2.0, -2.0, 0.0),
+            Vec3::new(4.0, 0.0, 0.0),
+            Vec3::new(0.0, 4.0, 0.0),
+        ),
+        (
+            Colour::new(0.2, 0.2, 1.0),
+            Point3::new(3.0, -2.0, 1.0),
+            Vec3::new(0.0, 0.0, 4.0),
+            Vec3::new(0.0, 4.0, 0.0),
+        ),
+        (
+            Colour::new(1.0, 0.5, 0.0),
+            Point3::new(-2.0, 3.0, 1.0),
+            Vec3::new(4.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 4.0),
+        ),
+        (
+            Colour::new(0.2, 0.8, 0.8),
+            Point3::new(-2.0, -3.0, 5.0),
+            Vec3::new(4.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, -4.0),
+        ),
+    ];
+    for (albedo, q, u, v) in sides {
+        world.add(Box::new(Quad::new(
+            q,
+            u,
+            v,
+            MaterialEnum::Lambertian(Lambertian::new(albedo)),
+        )));
+    }
+
+    Scene {
+        camera,
+        world,
+        lights: HittableList::new(),
+        diagnostics: Vec::new(),
+    }
+}
+
+pub fn cornell_box() -> Scene {
+    cornell(false)
+}
+
+/// The Cornell box with its two boxes replaced by volumes of smoke and fog.
+pub fn cornell_smoke() -> Scene {
+    cornell(true)
+}
+
+fn cornell(smoke: bool) -> Scene {
+    let mut camera = Camera::new(
+        1.0,
+        600,
+        200,
+        50,
+        40,
+        Point3::new(278.0, 278.0, -800.0),
+        Point3::new(278.0, 278.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        0.0,
+        10.0,
+    );
+    camera.background = Background::Black;
+
+    let mut world = HittableList::new();
+
+    let red = MaterialEnum::Lambertian(Lambertian::new(Colour::new(0.65, 0.05, 0.05)));
+    let white = MaterialEnum::Lambertian(Lambertian::new(Colour::new(0.73, 0.73, 0.73)));
+    let green = MaterialEnum::Lambertian(Lambertian::new(Colour::new(0.12, 0.45, 0.15)));
+    let light = MaterialEnum::DiffuseLight(DiffuseLight::new(Colour::new(15.0, 15.0, 15.0)));
+
+    world.add(Box::new(Quad::new(
+        Point3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        green,
+    )));
+    world.add(Box::new(Quad::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        red,
+    )));
+    world.add(Box::new(Quad::new(
+        Point3::new(343.0, 554.0, 332.0),
+        Vec3::new(-130.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, -105.0),
+        light,
+    )));
+    let mut lights = HittableList::new();
+    lights.add(Box::new(Quad::new(
+        Point3::new(343.0, 554.0, 332.0),
+        Vec3::new(-130.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, -105.0),
+        MaterialEnum::default(),
+    )));
+    world.add(Box::new(Quad::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 555.0),
+        white.clone(),
+    )));
+    world.add(Box::new(Quad::new(
+        Point3::new(555.0, 555.0, 555.0),
+        Vec3::new(-555.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, -555.0),
+        white.clone(),
+    )));
+    world.add(Box::new(Quad::new(
+        Point3::new(0.0, 0.0, 555.0),
+        Vec3::new(555.0, 0.0, 0.0),
+        Vec3::new(0.0, 555.0, 0.0),
+        white.clone(),
+    )));
+
+    let tall_box: Arc<dyn Hittable> = Arc::new(make_box(
+        Point3::new(0.0, 0.0, 0.0),
+        Point3::new(165.0, 330.0, 165.0),
+        white.clone(),
+    ));
+    let tall_box = Instance::new(
+        tall_box,
+        Transform::rotate_y(15.0).then(Transform::translate(Vec3::new(265.0, 0.0, 295.0))),
+    );
+
+    let short_box: Arc<dyn Hittable> = Arc::new(make_box(
+        Point3::new(0.0, 0.0, 0.0),
+        Point3::new(165.0, 165.0, 165.0),
+        white,
+    ));
+    let short_box = Instance::new(
+        short_box,
+        Transform::rotate_y(-18.0).then(Transform::translate(Vec3::new(130.0, 0.0, 65.0))),
+    );
+
+    match smoke {
+        true => {
+            world.add(Box::new(ConstantMedium::new(
+                Box::new(tall_box),
+                0.01,
+                Colour::new(0.0, 0.0, 0.0),
+            )));
+            world.add(Box::new(ConstantMedium::new(
+                Box::new(short_box),
+                0.01,
+                Colour::new(1.0, 1.0, 1.0),
+            )));
+        }
+        false => {
+            world.add(Box::new(tall_box));
+            world.add(Box::new(short_box));
+        }
+    }
+
+    Scene {
+        camera,
+        world,
+        lights,
+        diagnostics: Vec::new(),
+    }
+}
@@ -0,0 +1,180 @@
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+use crate::{colour::Colour, tonemap::DisplayTransform};
+
+#[derive(Clone, Debug)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<Colour>,
+    sample_counts: Vec<u32>,
+}
+
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![Colour::default(); width * height],
+            sample_counts: vec![0; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[Colour] {
+        &self.pixels
+    }
+
+    /// Number of samples averaged into each pixel, in the same order as [`Framebuffer::pixels`].
+    pub fn sample_counts(&self) -> &[u32] {
+        &self.sample_counts
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> Colour {
+        self.pixels[j * self.width + i]
+    }
+
+    pub fn set(&mut self, i: usize, j: usize, colour: Colour) {
+        self.pixels[j * self.width + i] = colour;
+    }
+
+    /// Shades each pixel by the samples it took, from black for none through red and
+    /// yellow to white for the most taken by any pixel.
+    pub fn sample_heatmap(&self) -> Framebuffer {
+        let max = self.sample_counts.iter().copied().max().unwrap_or(0).max(1) as f64;
+        let mut heatmap = Framebuffer::new(self.width, self.height);
+        heatmap.sample_counts.clone_from(&self.sample_counts);
+        for (pixel, &count) in heatmap.pixels.iter_mut().zip(&self.sample_counts) {
+            let t = 3.0 * count as f64 / max;
+            *pixel = Colour::new(
+                t.min(1.0),
+                (t - 1.0).clamp(0.0, 1.0),
+                (t - 2.0).clamp(0.0, 1.0),
+            );
+        }
+        heatmap
+    }
+
+    pub fn to_rgb8(&self, display: &DisplayTransform) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|p| display.to_rgb8(*p))
+            .collect()
+    }
+
+    pub fn to_rgb32f(&self) -> Vec<f32> {
+        self.pixels
+            .iter()
+            .flat_map(|p| [p.x() as f32, p.y() as f32, p.z() as f32])
+            .collect()
+    }
+}
+
+/// A framebuffer that render threads fill concurrently without locking. Each pixel
+/// is written by a single thread at a time, so relaxed stores are enough; joining the
+/// threads publishes the writes before [`SharedFramebuffer::to_framebuffer`] reads them.
+pub struct SharedFramebuffer {
+    width: usize,
+    height: usize,
+    /// Bit patterns of each pixel's `f64` channels.
+    pixels: Vec<[AtomicU64; 3]>,
+    sample_counts: Vec<AtomicU32>,
+}
+
+impl SharedFramebuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: (0..width * height).map(|_| Default::default()).collect(),
+            sample_counts: (0..width * height).map(|_| AtomicU32::new(0)).collect(),
+        }
+    }
+
+    pub fn set(&self, i: usize, j: usize, colour: Colour, sample_count: u32) {
+        let index = j * self.width + i;
+        for (channel, value) in self.pixels[index]
+            .iter()
+            .zip([colour.x(), colour.y(), colour.z()])
+        {
+            channel.store(value.to_bits(), Ordering::Relaxed);
+        }
+        self.sample_counts[index].store(sample_count, Ordering::Relaxed);
+    }
+
+    /// Copies out the pixels written so far.
+    pub fn to_framebuffer(&self) -> Framebuffer {
+        let load = |channel: &AtomicU64| f64::from_bits(channel.load(Ordering::Relaxed));
+        Framebuffer {
+            width: self.width,
+            height: self.height,
+            pixels: self
+                .pixels
+                .iter()
+                .map(|[r, g, b]| Colour::new(load(r), load(g), load(b)))
+                .collect(),
+            sample_counts: self
+                .sample_counts
+                .iter()
+                .map(|count| count.load(Ordering::Relaxed))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tonemap::ToneMapper;
+
+    #[test]
+    fn test_buffers_are_row_major() {
+        let mut fb = Framebuffer::new(3, 2);
+        fb.set(2, 0, Colour::new(1.0, 0.0, 0.0));
+        fb.set(0, 1, Colour::new(0.0, 0.5, 0.0));
+
+        let rgb8 = fb.to_rgb8(&DisplayTransform::new(ToneMapper::Clamp, 0.0));
+        assert_eq!(rgb8.len(), 3 * 2 * 3);
+        assert_eq!(rgb8[6..9], [255, 0, 0]);
+        assert!(rgb8[10] > 0 && rgb8[9] == 0 && rgb8[11] == 0);
+
+        let rgb32f = fb.to_rgb32f();
+        assert_eq!(rgb32f.len(), 3 * 2 * 3);
+        assert_eq!(rgb32f[6..9], [1.0, 0.0, 0.0]);
+        assert_eq!(rgb32f[9..12], [0.0, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn test_shared_framebuffer_copies_out_pixels_and_counts() {
+        let shared = SharedFramebuffer::new(4, 3);
+        shared.set(1, 2, Colour::new(0.25, 0.5, 0.75), 7);
+
+        let fb = shared.to_framebuffer();
+        assert_eq!((fb.width(), fb.height()), (4, 3));
+        assert_eq!(fb.get(1, 2).y(), 0.5);
+        assert_eq!(fb.sample_counts()[2 * 4 + 1], 7);
+        assert_eq!(fb.sample_counts().iter().sum::<u32>(), 7);
+    }
+
+    #[test]
+    fn test_sample_heatmap_ramps_to_white() {
+        let shared = SharedFramebuffer::new(2, 1);
+        shared.set(0, 0, Colour::default(), 0);
+        shared.set(1, 0, Colour::default(), 12);
+
+        let heatmap = shared.to_framebuffer().sample_heatmap();
+        assert_eq!((heatmap.width(), heatmap.height()), (2, 1));
+        assert_eq!(heatmap.get(0, 0).length(), 0.0);
+        assert_eq!(
+            heatmap.get(1, 0).x() + heatmap.get(1, 0).y() + heatmap.get(1, 0).z(),
+            3.0
+        );
+    }
+}
@@ -1,24 +1,67 @@
 use crate::{
+    aabb::Aabb,
     interval::Interval,
-    material::MaterialEnum,
+    material::{DefaultMaterial, MaterialEnum},
     ray::{Point3, Ray},
     vec3::Vec3,
 };
 
 pub trait Hittable: Send + Sync {
-    fn hit(&self, r: &Ray, interval: Interval, rec: &mut HitRecord) -> bool;
+    fn hit<'a>(&'a self, r: &Ray, interval: Interval, rec: &mut HitRecord<'a>) -> bool;
+    fn bounding_box(&self) -> Aabb;
+
+    /// Solid-angle density, as seen from `origin`, of [`Hittable::random`] producing
+    /// `direction`. Shapes that cannot be sampled as lights return zero.
+    fn pdf_value(&self, _origin: Point3, _direction: Vec3) -> f64 {
+        0.0
+    }
+
+    /// Direction from `origin` towards the point on this shape that the uniform
+    /// sample `u` in [0, 1)² maps to.
+    fn random(&self, _origin: Point3, _u: (f64, f64)) -> Vec3 {
+        Vec3::new(1.0, 0.0, 0.0)
+    }
+}
+
+/// Converts the uniform area density `1 / area` at the surface hit by a ray from
+/// the origin along `direction` into a density over solid angle.
+pub(crate) fn area_pdf_to_solid_angle(direction: Vec3, rec: &HitRecord, area: f64) -> f64 {
+    let distance_squared = rec.t * rec.t * direction.length_squared();
+    let cosine = (Vec3::dot(direction, rec.normal) / direction.length()).abs();
+    match cosine < 1e-8 {
+        true => 0.0,
+        false => distance_squared / (cosine * area),
+    }
 }
 
-#[derive(Default, Copy, Clone)]
-pub struct HitRecord {
+static DEFAULT_MATERIAL: MaterialEnum = MaterialEnum::Default(DefaultMaterial);
+
+#[derive(Copy, Clone)]
+pub struct HitRecord<'a> {
     pub p: Point3,
     pub normal: Vec3,
-    pub material: MaterialEnum,
+    pub material: &'a MaterialEnum,
     pub t: f64,
+    pub u: f64,
+    pub v: f64,
     pub front_face: bool,
 }
 
-impl HitRecord {
+impl Default for HitRecord<'_> {
+    fn default() -> Self {
+        Self {
+            p: Point3::default(),
+            normal: Vec3::default(),
+            material: &DEFAULT_MATERIAL,
+            t: 0.0,
+            u: 0.0,
+            v: 0.0,
+            front_face: false,
+        }
+    }
+}
+
+impl HitRecord<'_> {
     pub fn set_face_normal(&mut self, r: &Ray, outward_normal: Vec3) {
         self.front_face = Vec3::dot(r.direction(), outward_normal) < 0.0;
         match self.front_face {
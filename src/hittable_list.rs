@@ -1,30 +1,54 @@
 use crate::{
+    aabb::Aabb,
     hittable::{HitRecord, Hittable},
     interval::Interval,
+    ray::Point3,
+    vec3::Vec3,
 };
 
 pub struct HittableList {
     objects: Vec<Box<dyn Hittable + Send + Sync>>,
+    bbox: Aabb,
 }
 
 impl HittableList {
     pub fn new() -> Self {
         HittableList {
             objects: Vec::new(),
+            bbox: Aabb::empty(),
         }
     }
 
     pub fn add(&mut self, object: Box<dyn Hittable + Send + Sync>) {
+        self.bbox = Aabb::enclosing(&self.bbox, &object.bounding_box());
         self.objects.push(object);
     }
+
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    pub fn into_objects(self) -> Vec<Box<dyn Hittable + Send + Sync>> {
+        self.objects
+    }
+}
+
+impl Default for HittableList {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Hittable for HittableList {
-    fn hit(
-        &self,
+    fn hit<'a>(
+        &'a self,
         r: &crate::ray::Ray,
         interval: Interval,
-        rec: &mut crate::hittable::HitRecord,
+        rec: &mut crate::hittable::HitRecord<'a>,
     ) -> bool {
         let mut hit_anything = false;
         let mut closest_so_far = interval.max();
@@ -44,4 +68,34 @@ impl Hittable for HittableList {
 
         hit_anything
     }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox.clone()
+    }
+
+    /// Mixture density: each object is chosen with equal probability.
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        if self.objects.is_empty() {
+            return 0.0;
+        }
+
+        let weight = 1.0 / self.objects.len() as f64;
+        self.objects
+            .iter()
+            .map(|object| weight * object.pdf_value(origin, direction))
+            .sum()
+    }
+
+    fn random(&self, origin: Point3, u: (f64, f64)) -> Vec3 {
+        if self.objects.is_empty() {
+            return Vec3::new(1.0, 0.0, 0.0);
+        }
+
+        // Pick an object with the first coordinate, then stretch what is left of it
+        // back over [0, 1) so the chosen object still sees a uniform sample.
+        let n = self.objects.len();
+        let scaled = u.0 * n as f64;
+        let index = (scaled as usize).min(n - 1);
+        self.objects[index].random(origin, (scaled - index as f64, u.1))
+    }
 }
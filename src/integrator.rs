@@ -0,0 +1,267 @@
+use crate::{
+    background::Background,
+    colour::Colour,
+    hittable::{HitRecord, Hittable},
+    interval::Interval,
+    material::{Material, ScatterRecord},
+    pdf::{power_heuristic, ScatterPdf},
+    ray::Ray,
+    sampler::Sampler,
+};
+
+/// Estimates the radiance carried along camera rays.
+pub trait Integrator: Send + Sync {
+    /// `lights` holds the emitters that may be sampled directly; each must also be
+    /// part of `world`. `sampler` has already been started on the current pixel sample.
+    fn radiance(
+        &self,
+        r: &Ray,
+        world: &dyn Hittable,
+        lights: &dyn Hittable,
+        background: &Background,
+        sampler: &mut Sampler,
+    ) -> Colour;
+}
+
+/// Unidirectional path tracer. At non-delta scattering events both the material and
+/// the lights are sampled, and the two estimates are combined with the power
+/// heuristic. Paths are cut at `max_depth` bounces, and from `russian_roulette_depth`
+/// bounces on are randomly terminated by Russian roulette.
+#[derive(Clone, Copy, Debug)]
+pub struct PathTracer {
+    pub max_depth: i64,
+    pub russian_roulette_depth: i64,
+}
+
+impl PathTracer {
+    pub fn new(max_depth: i64, russian_roulette_depth: i64) -> Self {
+        Self {
+            max_depth,
+            russian_roulette_depth,
+        }
+    }
+
+    /// MIS-weighted radiance from a point sampled on `lights`.
+    fn sample_light(
+        r_in: &Ray,
+        rec: &HitRecord,
+        srec: &ScatterRecord,
+        world: &dyn Hittable,
+        lights: &dyn Hittable,
+        u: (f64, f64),
+    ) -> Colour {
+        let direction = lights.random(rec.p, u);
+        let light_pdf = lights.pdf_value(rec.p, direction);
+        if light_pdf <= 0.0 {
+            return Colour::new(0.0, 0.0, 0.0);
+        }
+
+        let shadow_ray = Ray::with_time(rec.p, direction, r_in.time());
+        let scattering_pdf = rec.material.scattering_pdf(r_in, rec, &shadow_ray);
+        if scattering_pdf <= 0.0 {
+            return Colour::new(0.0, 0.0, 0.0);
+        }
+
+        let mut light_rec = HitRecord::default();
+        if !world.hit(
+            &shadow_ray,
+            Interval::new(0.001, f64::INFINITY),
+            &mut light_rec,
+        ) {
+            return Colour::new(0.0, 0.0, 0.0);
+        }
+
+        let emitted = light_rec
+            .material
+            .emitted(light_rec.u, light_rec.v, light_rec.p);
+        let weight = power_heuristic(light_pdf, srec.pdf.value(direction));
+        weight * scattering_pdf * emitted / light_pdf
+    }
+}
+
+impl Integrator for PathTracer {
+    fn radiance(
+        &self,
+        r: &Ray,
+        world: &dyn Hittable,
+        lights: &dyn Hittable,
+        background: &Background,
+        sampler: &mut Sampler,
+    ) -> Colour {
+        let mut radiance = Colour::new(0.0, 0.0, 0.0);
+        let mut throughput = Colour::new(1.0, 1.0, 1.0);
+        // MIS weight for emission found by the current ray, which light sampling at
+        // the previous vertex may also have accounted for.
+        let mut emission_weight = 1.0;
+        let mut ray = *r;
+
+        for bounce in 0..self.max_depth {
+            let mut rec = HitRecord::default();
+            if !world.hit(&ray, Interval::new(0.001, f64::INFINITY), &mut rec) {
+                radiance += throughput * background.colour(&ray);
+                break;
+            }
+
+            let emitted = rec.material.emitted(rec.u, rec.v, rec.p);
+            radiance += emission_weight * throughput * emitted;
+
+            // Every bounce draws the same dimensions in the same order, whichever are
+            // used, so a given dimension always drives the same decision.
+            let light_sample = sampler.get_2d();
+            let bsdf_sample = sampler.get_2d();
+            let roulette_sample = sampler.get_1d();
+
+            let mut srec = ScatterRecord::default();
+            if !rec.material.scatter(&ray, &rec, &mut srec, sampler.rng()) {
+                break;
+            }
+
+            let scattered = match srec.pdf {
+                ScatterPdf::Delta(scattered) => {
+                    throughput = throughput * srec.attenuation;
+                    emission_weight = 1.0;
+                    scattered
+                }
+                _ => {
+                    let direct = Self::sample_light(&ray, &rec, &srec, world, lights, light_sample);
+                    radiance += throughput * srec.attenuation * direct;
+
+                    let direction = srec.pdf.generate(bsdf_sample);
+                    let bsdf_pdf = srec.pdf.value(direction);
+                    if bsdf_pdf <= 0.0 {
+                        break;
+                    }
+
+                    let scattered = Ray::with_time(rec.p, direction, ray.time());
+                    let scattering_pdf = rec.material.scattering_pdf(&ray, &rec, &scattered);
+                    throughput = throughput * srec.attenuation * (scattering_pdf / bsdf_pdf);
+                    emission_weight = power_heuristic(bsdf_pdf, lights.pdf_value(rec.p, direction));
+                    scattered
+                }
+            };
+
+            // Past the minimum depth, continue with a probability tied to the throughput
+            // and scale survivors up by it so the estimate stays unbiased.
+            if bounce + 1 >= self.russian_roulette_depth {
+                let survival = throughput.max_component().min(1.0);
+                if roulette_sample >= survival {
+                    break;
+                }
+                throughput /= survival;
+            }
+
+            ray = scattered;
+        }
+
+        radiance
+    }
+}
+
+/// Debug integrator shading each hit by its surface normal.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Normals;
+
+impl Integrator for Normals {
+    fn radiance(
+        &self,
+        r: &Ray,
+        world: &dyn Hittable,
+        _lights: &dyn Hittable,
+        background: &Background,
+        _sampler: &mut Sampler,
+    ) -> Colour {
+        let mut rec = HitRecord::default();
+        if !world.hit(r, Interval::new(0.001, f64::INFINITY), &mut rec) {
+            return background.colour(r);
+        }
+
+        0.5 * (rec.normal + Colour::new(1.0, 1.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hittable_list::HittableList,
+        material::{DiffuseLight, Lambertian, MaterialEnum},
+        quad::Quad,
+        ray::Point3,
+        sampler::SamplerKind,
+        vec3::Vec3,
+    };
+
+    fn light() -> Quad {
+        Quad::new(
+            Point3::new(-1.0, 2.0, -1.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 2.0),
+            MaterialEnum::DiffuseLight(DiffuseLight::new(Colour::new(4.0, 4.0, 4.0))),
+        )
+    }
+
+    fn mean_radiance(
+        world: &HittableList,
+        lights: &HittableList,
+        russian_roulette_depth: i64,
+        samples: usize,
+        seed: u64,
+    ) -> f64 {
+        let integrator = PathTracer::new(2, russian_roulette_depth);
+        let r = Ray::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let mut sampler = Sampler::new(SamplerKind::Independent, samples as i64, seed);
+        let total: f64 = (0..samples)
+            .map(|s| {
+                sampler.start_pixel_sample(0, 0, s);
+                integrator
+                    .radiance(&r, world, lights, &Background::Black, &mut sampler)
+                    .x()
+            })
+            .sum();
+        total / samples as f64
+    }
+
+    fn lit_floor() -> HittableList {
+        let mut world = HittableList::new();
+        world.add(Box::new(Quad::new(
+            Point3::new(-50.0, 0.0, -50.0),
+            Vec3::new(0.0, 0.0, 100.0),
+            Vec3::new(100.0, 0.0, 0.0),
+            MaterialEnum::Lambertian(Lambertian::new(Colour::new(0.5, 0.5, 0.5))),
+        )));
+        world.add(Box::new(light()));
+        world
+    }
+
+    #[test]
+    fn test_light_sampling_matches_bounce_estimate() {
+        let world = lit_floor();
+        let mut lights = HittableList::new();
+        lights.add(Box::new(light()));
+
+        let bounces = mean_radiance(&world, &HittableList::new(), 2, 200_000, 7);
+        let sampled = mean_radiance(&world, &lights, 2, 20_000, 8);
+        assert!(
+            (bounces - sampled).abs() < 0.03 * bounces,
+            "{} vs {}",
+            bounces,
+            sampled
+        );
+    }
+
+    #[test]
+    fn test_russian_roulette_is_unbiased() {
+        let world = lit_floor();
+        let mut lights = HittableList::new();
+        lights.add(Box::new(light()));
+
+        let full = mean_radiance(&world, &lights, 2, 20_000, 11);
+        let roulette = mean_radiance(&world, &lights, 0, 80_000, 12);
+        assert!(
+            (full - roulette).abs() < 0.03 * full,
+            "{} vs {}",
+            full,
+            roulette
+        );
+    }
+}
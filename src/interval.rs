@@ -1,6 +1,6 @@
 use std::ops::Range;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Interval {
     range: Range<f64>,
 }
@@ -10,6 +10,18 @@ impl Interval {
         Self { range: min..max }
     }
 
+    pub fn empty() -> Self {
+        Self::new(f64::INFINITY, f64::NEG_INFINITY)
+    }
+
+    pub fn universe() -> Self {
+        Self::new(f64::NEG_INFINITY, f64::INFINITY)
+    }
+
+    pub fn enclosing(a: &Interval, b: &Interval) -> Self {
+        Self::new(a.min().min(b.min()), a.max().max(b.max()))
+    }
+
     pub fn min(&self) -> f64 {
         self.range.start
     }
@@ -18,10 +30,23 @@ impl Interval {
         self.range.end
     }
 
+    pub fn size(&self) -> f64 {
+        self.range.end - self.range.start
+    }
+
+    pub fn contains(&self, x: f64) -> bool {
+        self.range.start <= x && x <= self.range.end
+    }
+
     pub fn surrounds(&self, x: f64) -> bool {
         self.range.start < x && x < self.range.end
     }
 
+    pub fn expand(&self, delta: f64) -> Self {
+        let padding = delta / 2.0;
+        Self::new(self.range.start - padding, self.range.end + padding)
+    }
+
     pub fn clamp(&self, x: f64) -> f64 {
         match x {
             x if x < self.range.start => self.range.start,
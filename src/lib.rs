@@ -1,11 +1,31 @@
+pub mod aabb;
+pub mod background;
+pub mod bvh;
 pub mod camera;
 pub mod colour;
+pub mod constant_medium;
+pub mod demos;
+pub mod framebuffer;
 pub mod hittable;
 pub mod hittable_list;
+pub mod integrator;
 pub mod interval;
 pub mod material;
+pub mod mesh;
 pub mod metal;
+pub mod obj;
+pub mod output;
+pub mod pdf;
+pub mod perlin;
+pub mod plane;
+pub mod quad;
 pub mod ray;
+pub mod sampler;
+pub mod scene;
 pub mod sphere;
+pub mod texture;
+pub mod tonemap;
+pub mod transform;
+pub mod triangle;
 pub mod utils;
 pub mod vec3;
@@ -1,102 +1,290 @@
-use std::{sync::Arc, time::Instant};
+use std::{
+    path::PathBuf,
+    process::exit,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
+use clap::{builder::PossibleValuesParser, Parser, ValueEnum};
+use rand::SeedableRng;
 use ray_tracing::{
-    camera::Camera,
-    colour::Colour,
-    hittable_list::HittableList,
-    material::{Dielectric, Lambertian, MaterialEnum, Metal},
-    ray::Point3,
-    sphere::Sphere,
-    utils::{random_double, random_double_in_range},
-    vec3::Vec3,
+    bvh::BvhNode,
+    camera::AdaptiveSampling,
+    demos::{demo_scene, DEMO_SCENES},
+    integrator::{Integrator, Normals},
+    output::{is_supported_format, replace_image, write_image, SUPPORTED_FORMATS},
+    sampler::SamplerKind,
+    scene::{load_scene, Scene},
+    tonemap::{DisplayTransform, ToneMapper},
+    utils::RenderRng,
 };
 
+#[derive(Parser)]
+#[command(about = "Render a scene with the path tracer")]
+struct Args {
+    /// Scene description file to render
+    #[arg(short, long, conflicts_with = "demo")]
+    scene: Option<PathBuf>,
+
+    /// Built-in demo scene to render when no scene file is given
+    #[arg(short, long, default_value = "final", value_parser = PossibleValuesParser::new(DEMO_SCENES))]
+    demo: String,
+
+    /// Output image; the format is chosen from the extension
+    #[arg(short, long, default_value = "image.png")]
+    output: PathBuf,
+
+    /// Override the image width in pixels
+    #[arg(long, value_parser = clap::value_parser!(i64).range(1..))]
+    width: Option<i64>,
+
+    /// Override the image height in pixels, adjusting the aspect ratio
+    #[arg(long, value_parser = clap::value_parser!(i64).range(1..))]
+    height: Option<i64>,
+
+    /// Override the number of samples per pixel
+    #[arg(long, value_parser = clap::value_parser!(i64).range(1..))]
+    samples_per_pixel: Option<i64>,
+
+    /// Override the maximum ray bounce depth
+    #[arg(long, value_parser = clap::value_parser!(i64).range(1..))]
+    max_depth: Option<i64>,
+
+    /// Override the bounce count after which paths may be terminated early
+    #[arg(long, value_parser = clap::value_parser!(i64).range(0..))]
+    russian_roulette_depth: Option<i64>,
+
+    /// Enable adaptive sampling, stopping pixels whose relative standard error falls to this
+    #[arg(long)]
+    adaptive_threshold: Option<f64>,
+
+    /// Minimum samples per pixel when adaptive sampling is enabled
+    #[arg(long, value_parser = clap::value_parser!(i64).range(1..))]
+    min_samples_per_pixel: Option<i64>,
+
+    /// Also write an image of how many samples each pixel took
+    #[arg(long)]
+    sample_heatmap: Option<PathBuf>,
+
+    /// Render one sample per pixel per pass, rewriting the output after each pass
+    #[arg(long)]
+    progressive: bool,
+
+    /// Render progressively for this many seconds instead of a fixed number of samples
+    #[arg(long, value_name = "SECONDS")]
+    time_budget: Option<f64>,
+
+    /// Number of render threads (defaults to the available parallelism)
+    #[arg(short = 'j', long, value_parser = clap::value_parser!(u64).range(1..))]
+    threads: Option<u64>,
+
+    /// Seed for scene generation and sampling
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Override how sample positions are generated
+    #[arg(long, value_enum)]
+    sampler: Option<SamplerChoice>,
+
+    /// Integrator used to estimate the radiance of each sample
+    #[arg(long, value_enum, default_value_t = IntegratorKind::Path)]
+    integrator: IntegratorKind,
+
+    /// Tone mapping operator used for 8-bit output
+    #[arg(long, value_enum, default_value_t = ToneMap::Clamp)]
+    tonemap: ToneMap,
+
+    /// Exposure adjustment in stops applied before tone mapping
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    exposure: f64,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum IntegratorKind {
+    Path,
+    Normals,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SamplerChoice {
+    Independent,
+    Stratified,
+    Halton,
+    Sobol,
+    BlueNoise,
+}
+
+impl From<SamplerChoice> for SamplerKind {
+    fn from(choice: SamplerChoice) -> Self {
+        match choice {
+            SamplerChoice::Independent => SamplerKind::Independent,
+            SamplerChoice::Stratified => SamplerKind::Stratified,
+            SamplerChoice::Halton => SamplerKind::Halton,
+            SamplerChoice::Sobol => SamplerKind::Sobol,
+            SamplerChoice::BlueNoise => SamplerKind::BlueNoise,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ToneMap {
+    Clamp,
+    Reinhard,
+    ExtendedReinhard,
+    Aces,
+    Hable,
+}
+
+impl From<ToneMap> for ToneMapper {
+    fn from(tone_map: ToneMap) -> Self {
+        match tone_map {
+            ToneMap::Clamp => ToneMapper::Clamp,
+            ToneMap::Reinhard => ToneMapper::Reinhard,
+            ToneMap::ExtendedReinhard => ToneMapper::ExtendedReinhard { white_point: 4.0 },
+            ToneMap::Aces => ToneMapper::Aces,
+            ToneMap::Hable => ToneMapper::Hable,
+        }
+    }
+}
+
 fn main() {
-    let camera = Camera::new(
-        16.0 / 9.0,
-        1200,
-        500,
-        50,
-        20,
-        Point3::new(13.0, 2.0, 3.0),
-        Point3::new(0.0, 0.0, 0.0),
-        Vec3::new(0.0, 1.0, 0.0),
-        0.6,
-        10.0,
-    );
-
-    let mut world = HittableList::new();
-
-    let ground = Lambertian::new(Colour::new(0.5, 0.5, 0.5));
-    world.add(Box::new(Sphere::new(
-        Point3::new(0.0, -1000.0, 0.0),
-        1000.0,
-        MaterialEnum::Lambertian(ground),
-    )));
-
-    for a in -11..11 {
-        for b in -11..11 {
-            let choose_mat = random_double();
-            let center = Point3::new(
-                a as f64 + 0.9 * random_double(),
-                0.2,
-                b as f64 + 0.9 * random_double(),
+    let args = Args::parse();
+
+    for path in std::iter::once(&args.output).chain(&args.sample_heatmap) {
+        if !is_supported_format(path) {
+            eprintln!(
+                "error: unsupported output format for {} (expected one of: {})",
+                path.display(),
+                SUPPORTED_FORMATS.join(", ")
             );
+            exit(2);
+        }
+    }
 
-            if (center - Point3::new(4.0, 0.2, 0.0)).length() > 0.9 {
-                if choose_mat < 0.8 {
-                    let albedo = Colour::random() * Colour::random();
-                    let sphere_material = Lambertian::new(albedo);
-                    world.add(Box::new(Sphere::new(
-                        center,
-                        0.2,
-                        MaterialEnum::Lambertian(sphere_material),
-                    )));
-                } else if choose_mat < 0.95 {
-                    let albedo = Colour::random_in_range(0.5, 1.0);
-                    let fuzz = random_double_in_range(0.0, 0.5);
-                    let sphere_material = Metal::new(albedo, fuzz);
-                    world.add(Box::new(Sphere::new(
-                        center,
-                        0.2,
-                        MaterialEnum::Metal(sphere_material),
-                    )));
-                } else {
-                    let sphere_material = Dielectric::new(1.5);
-                    world.add(Box::new(Sphere::new(
-                        center,
-                        0.2,
-                        MaterialEnum::Dielectric(sphere_material),
-                    )));
-                }
-            }
+    let scene = match &args.scene {
+        Some(path) => load_scene(path).unwrap_or_else(|e| {
+            eprintln!("error: {}: {}", path.display(), e);
+            exit(1);
+        }),
+        None => {
+            let mut rng = match args.seed {
+                Some(seed) => RenderRng::seed_from_u64(seed),
+                None => RenderRng::from_entropy(),
+            };
+            demo_scene(&args.demo, &mut rng).expect("demo name is validated by the parser")
         }
+    };
+
+    let Scene {
+        mut camera,
+        world,
+        lights,
+        diagnostics,
+    } = scene;
+
+    for diagnostic in &diagnostics {
+        eprintln!("warning: {}", diagnostic);
     }
 
-    let material1 = Dielectric::new(1.5);
-    world.add(Box::new(Sphere::new(
-        Point3::new(0.0, 1.0, 0.0),
-        1.0,
-        MaterialEnum::Dielectric(material1),
-    )));
+    if let Some(width) = args.width {
+        camera.image_width = width;
+    }
+    if let Some(height) = args.height {
+        camera.aspect_ratio = camera.image_width as f64 / height as f64;
+        camera.requested_height = Some(height);
+    }
+    if let Some(samples_per_pixel) = args.samples_per_pixel {
+        camera.samples_per_pixel = samples_per_pixel;
+    }
+    if let Some(max_depth) = args.max_depth {
+        camera.max_depth = max_depth;
+    }
+    if let Some(depth) = args.russian_roulette_depth {
+        camera.russian_roulette_depth = depth;
+    }
+    if let Some(sampler) = args.sampler {
+        camera.sampler = sampler.into();
+    }
+    if let Some(threshold) = args.adaptive_threshold {
+        if threshold <= 0.0 || !threshold.is_finite() {
+            eprintln!(
+                "error: adaptive threshold must be finite and positive, got {}",
+                threshold
+            );
+            exit(2);
+        }
+        let min_samples = camera
+            .adaptive
+            .map_or(AdaptiveSampling::DEFAULT_MIN_SAMPLES, |a| a.min_samples);
+        camera.adaptive = Some(AdaptiveSampling::new(threshold, min_samples));
+    }
+    if let Some(min_samples) = args.min_samples_per_pixel {
+        let Some(adaptive) = &mut camera.adaptive else {
+            eprintln!(
+                "error: --min-samples-per-pixel requires adaptive sampling, from \
+                 --adaptive-threshold or the scene's camera.adaptive"
+            );
+            exit(2);
+        };
+        adaptive.min_samples = min_samples;
+    }
+    if let Some(threads) = args.threads {
+        camera.num_threads = threads as usize;
+    }
+    camera.seed = args.seed;
+    camera.initialize();
 
-    let material2 = Lambertian::new(Colour::new(0.4, 0.2, 0.1));
-    world.add(Box::new(Sphere::new(
-        Point3::new(-4.0, 1.0, 0.0),
-        1.0,
-        MaterialEnum::Lambertian(material2),
-    )));
+    if !args.exposure.is_finite() {
+        eprintln!("error: exposure must be finite, got {}", args.exposure);
+        exit(2);
+    }
+    let display = DisplayTransform::new(args.tonemap.into(), args.exposure);
+    let integrator: Arc<dyn Integrator> = match args.integrator {
+        IntegratorKind::Path => Arc::new(camera.path_tracer()),
+        IntegratorKind::Normals => Arc::new(Normals),
+    };
 
-    let material3 = Metal::new(Colour::new(0.7, 0.6, 0.5), 0.0);
-    world.add(Box::new(Sphere::new(
-        Point3::new(4.0, 1.0, 0.0),
-        1.0,
-        MaterialEnum::Metal(material3),
-    )));
+    let time_budget = args.time_budget.map(|seconds| {
+        Duration::try_from_secs_f64(seconds).unwrap_or_else(|_| {
+            eprintln!("error: invalid time budget: {}", seconds);
+            exit(2);
+        })
+    });
 
+    let world = Arc::new(BvhNode::new(world));
+    let lights = Arc::new(lights);
     let start_time = Instant::now();
-    camera.render(Arc::new(world));
+    let image = match args.progressive || time_budget.is_some() {
+        true => camera.render_progressive(world, lights, integrator, time_budget, |pass, image| {
+            match time_budget {
+                Some(_) => eprint!("\rPass {}", pass),
+                None => eprint!("\rPass {} of {}", pass, camera.samples_per_pixel),
+            }
+            if let Err(e) = replace_image(image, &args.output, &display) {
+                eprintln!();
+                eprintln!("error: {}: {}", args.output.display(), e);
+                exit(1);
+            }
+        }),
+        false => camera.render(world, lights, integrator, |finished, total| {
+            eprint!("\rRendered {} of {} tiles", finished, total)
+        }),
+    };
+    eprintln!();
     let duration = start_time.elapsed();
 
-    eprintln!("Done in: {} seconds", duration.as_secs())
+    eprintln!("Done in: {} seconds", duration.as_secs());
+
+    if let Err(e) = write_image(&image, &args.output, &display) {
+        eprintln!("error: {}: {}", args.output.display(), e);
+        exit(1);
+    }
+
+    if let Some(path) = &args.sample_heatmap {
+        let heatmap_display = DisplayTransform::new(ToneMapper::Clamp, 0.0);
+        if let Err(e) = write_image(&image.sample_heatmap(), path, &heatmap_display) {
+            eprintln!("error: {}: {}", path.display(), e);
+            exit(1);
+        }
+    }
 }
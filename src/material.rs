@@ -1,19 +1,41 @@
 use crate::{
     colour::Colour,
     hittable::HitRecord,
-    ray::Ray,
-    utils::random_double,
+    pdf::ScatterPdf,
+    ray::{Point3, Ray},
+    texture::{Texture, TextureEnum},
+    utils::{random_double, RenderRng},
     vec3::{random_unit_vector, Vec3},
 };
 
-pub trait Material: Copy + Clone {
+/// The outcome of a scattering event. For non-delta distributions the
+/// reflectance towards a direction is `attenuation` times
+/// [`Material::scattering_pdf`] for that direction.
+#[derive(Clone, Copy, Default)]
+pub struct ScatterRecord {
+    pub attenuation: Colour,
+    pub pdf: ScatterPdf,
+}
+
+pub trait Material: Clone {
+    /// Fills `srec` and returns true if the incoming ray is scattered rather than absorbed.
     fn scatter(
         &self,
         r_in: &Ray,
         rec: &HitRecord,
-        attenuation: &mut Colour,
-        scattered: &mut Ray,
+        srec: &mut ScatterRecord,
+        rng: &mut RenderRng,
     ) -> bool;
+
+    fn emitted(&self, _u: f64, _v: f64, _p: Point3) -> Colour {
+        Colour::new(0.0, 0.0, 0.0)
+    }
+
+    /// Solid-angle density of the material scattering into `scattered`. Zero for
+    /// materials with a delta distribution, whose direction a light cannot choose.
+    fn scattering_pdf(&self, _r_in: &Ray, _rec: &HitRecord, _scattered: &Ray) -> f64 {
+        0.0
+    }
 }
 
 #[derive(Default, Clone, Copy)]
@@ -24,21 +46,27 @@ impl Material for DefaultMaterial {
         &self,
         _r_in: &Ray,
         _rec: &HitRecord,
-        _attenuation: &mut Colour,
-        _scattered: &mut Ray,
+        _srec: &mut ScatterRecord,
+        _rng: &mut RenderRng,
     ) -> bool {
         false
     }
 }
 
-#[derive(Default, Clone, Copy)]
+#[derive(Default, Clone)]
 pub struct Lambertian {
-    albedo: Colour,
+    tex: TextureEnum,
 }
 
 impl Lambertian {
     pub fn new(albedo: Colour) -> Self {
-        Self { albedo }
+        Self {
+            tex: TextureEnum::from(albedo),
+        }
+    }
+
+    pub fn from_texture(tex: TextureEnum) -> Self {
+        Self { tex }
     }
 }
 
@@ -47,32 +75,36 @@ impl Material for Lambertian {
         &self,
         _r_in: &Ray,
         rec: &HitRecord,
-        attenuation: &mut Colour,
-        scattered: &mut Ray,
+        srec: &mut ScatterRecord,
+        _rng: &mut RenderRng,
     ) -> bool {
-        let mut scatter_direction = rec.normal + random_unit_vector();
-
-        if scatter_direction.near_zero() {
-            scatter_direction = rec.normal;
-        }
-        *scattered = Ray::new(rec.p, scatter_direction);
-        *attenuation = self.albedo;
+        srec.attenuation = self.tex.value(rec.u, rec.v, rec.p);
+        srec.pdf = ScatterPdf::Cosine(rec.normal);
 
         true
     }
+
+    fn scattering_pdf(&self, _r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        let cos_theta = Vec3::dot(rec.normal, scattered.direction().unit_vector());
+        cos_theta.max(0.0) / std::f64::consts::PI
+    }
 }
 
-#[derive(Default, Clone, Copy)]
+#[derive(Default, Clone)]
 pub struct Metal {
-    albedo: Colour,
+    tex: TextureEnum,
     fuzz: f64,
 }
 
 impl Metal {
     pub fn new(albedo: Colour, fuzz: f64) -> Self {
-        match fuzz < 1.0 {
-            true => Self { albedo, fuzz },
-            false => Self { albedo, fuzz: 1.0 },
+        Self::from_texture(TextureEnum::from(albedo), fuzz)
+    }
+
+    pub fn from_texture(tex: TextureEnum, fuzz: f64) -> Self {
+        Self {
+            tex,
+            fuzz: fuzz.min(1.0),
         }
     }
 }
@@ -82,15 +114,15 @@ impl Material for Metal {
         &self,
         r_in: &Ray,
         rec: &HitRecord,
-        attenuation: &mut Colour,
-        scattered: &mut Ray,
+        srec: &mut ScatterRecord,
+        rng: &mut RenderRng,
     ) -> bool {
         let mut reflected = Vec3::reflect(r_in.direction(), rec.normal);
-        reflected = reflected.unit_vector() + (self.fuzz * random_unit_vector());
-        *scattered = Ray::new(rec.p, reflected);
-        *attenuation = self.albedo;
+        reflected = reflected.unit_vector() + (self.fuzz * random_unit_vector(rng));
+        srec.attenuation = self.tex.value(rec.u, rec.v, rec.p);
+        srec.pdf = ScatterPdf::Delta(Ray::with_time(rec.p, reflected, r_in.time()));
 
-        Vec3::dot(scattered.direction(), rec.normal) > 0.0
+        Vec3::dot(reflected, rec.normal) > 0.0
     }
 }
 
@@ -117,10 +149,10 @@ impl Material for Dielectric {
         &self,
         r_in: &Ray,
         rec: &HitRecord,
-        attenuation: &mut Colour,
-        scattered: &mut Ray,
+        srec: &mut ScatterRecord,
+        rng: &mut RenderRng,
     ) -> bool {
-        *attenuation = Colour::new(1.0, 1.0, 1.0);
+        srec.attenuation = Colour::new(1.0, 1.0, 1.0);
         let ri = match rec.front_face {
             true => 1.0 / self.refraction_index,
             false => self.refraction_index,
@@ -132,22 +164,94 @@ impl Material for Dielectric {
 
         let cannot_refract = ri * sin_theta > 1.0;
         let direction =
-            match cannot_refract || Dielectric::reflectance(cos_theta, ri) > random_double() {
+            match cannot_refract || Dielectric::reflectance(cos_theta, ri) > random_double(rng) {
                 true => Vec3::reflect(unit_direction, rec.normal),
                 false => Vec3::refract(unit_direction, rec.normal, ri),
             };
 
-        *scattered = Ray::new(rec.p, direction);
+        srec.pdf = ScatterPdf::Delta(Ray::with_time(rec.p, direction, r_in.time()));
         true
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Default, Clone)]
+pub struct DiffuseLight {
+    tex: TextureEnum,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Colour) -> Self {
+        Self {
+            tex: TextureEnum::from(emit),
+        }
+    }
+
+    pub fn from_texture(tex: TextureEnum) -> Self {
+        Self { tex }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(
+        &self,
+        _r_in: &Ray,
+        _rec: &HitRecord,
+        _srec: &mut ScatterRecord,
+        _rng: &mut RenderRng,
+    ) -> bool {
+        false
+    }
+
+    fn emitted(&self, u: f64, v: f64, p: Point3) -> Colour {
+        self.tex.value(u, v, p)
+    }
+}
+
+/// Phase function for participating media: scatters uniformly in all directions.
+#[derive(Default, Clone)]
+pub struct Isotropic {
+    tex: TextureEnum,
+}
+
+impl Isotropic {
+    pub fn new(albedo: Colour) -> Self {
+        Self {
+            tex: TextureEnum::from(albedo),
+        }
+    }
+
+    pub fn from_texture(tex: TextureEnum) -> Self {
+        Self { tex }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(
+        &self,
+        _r_in: &Ray,
+        rec: &HitRecord,
+        srec: &mut ScatterRecord,
+        _rng: &mut RenderRng,
+    ) -> bool {
+        srec.attenuation = self.tex.value(rec.u, rec.v, rec.p);
+        srec.pdf = ScatterPdf::Uniform;
+
+        true
+    }
+
+    fn scattering_pdf(&self, _r_in: &Ray, _rec: &HitRecord, _scattered: &Ray) -> f64 {
+        1.0 / (4.0 * std::f64::consts::PI)
+    }
+}
+
+#[derive(Clone)]
 pub enum MaterialEnum {
     Default(DefaultMaterial),
     Lambertian(Lambertian),
     Metal(Metal),
     Dielectric(Dielectric),
+    DiffuseLight(DiffuseLight),
+    Isotropic(Isotropic),
 }
 
 impl Material for MaterialEnum {
@@ -155,14 +259,38 @@ impl Material for MaterialEnum {
         &self,
         r_in: &Ray,
         rec: &HitRecord,
-        attenuation: &mut Colour,
-        scattered: &mut Ray,
+        srec: &mut ScatterRecord,
+        rng: &mut RenderRng,
     ) -> bool {
         match self {
-            MaterialEnum::Default(m) => m.scatter(r_in, rec, attenuation, scattered),
-            MaterialEnum::Lambertian(m) => m.scatter(r_in, rec, attenuation, scattered),
-            MaterialEnum::Metal(m) => m.scatter(r_in, rec, attenuation, scattered),
-            MaterialEnum::Dielectric(m) => m.scatter(r_in, rec, attenuation, scattered),
+            MaterialEnum::Default(m) => m.scatter(r_in, rec, srec, rng),
+            MaterialEnum::Lambertian(m) => m.scatter(r_in, rec, srec, rng),
+            MaterialEnum::Metal(m) => m.scatter(r_in, rec, srec, rng),
+            MaterialEnum::Dielectric(m) => m.scatter(r_in, rec, srec, rng),
+            MaterialEnum::DiffuseLight(m) => m.scatter(r_in, rec, srec, rng),
+            MaterialEnum::Isotropic(m) => m.scatter(r_in, rec, srec, rng),
+        }
+    }
+
+    fn emitted(&self, u: f64, v: f64, p: Point3) -> Colour {
+        match self {
+            MaterialEnum::Default(m) => m.emitted(u, v, p),
+            MaterialEnum::Lambertian(m) => m.emitted(u, v, p),
+            MaterialEnum::Metal(m) => m.emitted(u, v, p),
+            MaterialEnum::Dielectric(m) => m.emitted(u, v, p),
+            MaterialEnum::DiffuseLight(m) => m.emitted(u, v, p),
+            MaterialEnum::Isotropic(m) => m.emitted(u, v, p),
+        }
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        match self {
+            MaterialEnum::Default(m) => m.scattering_pdf(r_in, rec, scattered),
+            MaterialEnum::Lambertian(m) => m.scattering_pdf(r_in, rec, scattered),
+            MaterialEnum::Metal(m) => m.scattering_pdf(r_in, rec, scattered),
+            MaterialEnum::Dielectric(m) => m.scattering_pdf(r_in, rec, scattered),
+            MaterialEnum::DiffuseLight(m) => m.scattering_pdf(r_in, rec, scattered),
+            MaterialEnum::Isotropic(m) => m.scattering_pdf(r_in, rec, scattered),
         }
     }
 }
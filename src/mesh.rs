@@ -0,0 +1,410 @@
+use std::fmt;
+
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    interval::Interval,
+    material::MaterialEnum,
+    ray::{Point3, Ray},
+    triangle::{intersect, triangle_bounds},
+    vec3::Vec3,
+};
+
+const MAX_LEAF_FACES: usize = 4;
+/// Median splits halve each node, so no traversal needs more stack than this.
+const MAX_TRAVERSAL_DEPTH: usize = 64;
+
+/// A face indexing past the end of one of the mesh's buffers.
+#[derive(Debug)]
+pub struct MeshError {
+    pub face: usize,
+    pub missing: &'static str,
+}
+
+impl fmt::Display for MeshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "mesh face {} references a missing {}",
+            self.face, self.missing
+        )
+    }
+}
+
+impl std::error::Error for MeshError {}
+
+#[derive(Clone, Debug)]
+pub struct MeshFace {
+    pub positions: [usize; 3],
+    pub normals: Option<[usize; 3]>,
+    pub uvs: Option<[usize; 3]>,
+    pub material: usize,
+}
+
+impl MeshFace {
+    pub fn new(positions: [usize; 3]) -> Self {
+        Self {
+            positions,
+            normals: None,
+            uvs: None,
+            material: 0,
+        }
+    }
+}
+
+struct MeshNode {
+    bbox: Aabb,
+    start: usize,
+    count: usize,
+    left: usize,
+    right: usize,
+}
+
+/// Triangles sharing vertex, normal and UV buffers, accelerated by an
+/// internal BVH over the faces rather than one boxed hittable per face.
+pub struct TriangleMesh {
+    positions: Vec<Point3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<[f64; 2]>,
+    faces: Vec<MeshFace>,
+    materials: Vec<MaterialEnum>,
+    nodes: Vec<MeshNode>,
+}
+
+impl TriangleMesh {
+    pub fn new(
+        positions: Vec<Point3>,
+        normals: Vec<Vec3>,
+        uvs: Vec<[f64; 2]>,
+        faces: Vec<MeshFace>,
+        materials: Vec<MaterialEnum>,
+    ) -> Result<Self, MeshError> {
+        for (index, face) in faces.iter().enumerate() {
+            let missing = if face.positions.iter().any(|&i| i >= positions.len()) {
+                Some("vertex")
+            } else if face.normals.iter().flatten().any(|&i| i >= normals.len()) {
+                Some("normal")
+            } else if face.uvs.iter().flatten().any(|&i| i >= uvs.len()) {
+                Some("texture coordinate")
+            } else if face.material >= materials.len() {
+                Some("material")
+            } else {
+                None
+            };
+            if let Some(missing) = missing {
+                return Err(MeshError {
+                    face: index,
+                    missing,
+                });
+            }
+        }
+
+        let mut mesh = Self {
+            positions,
+            normals,
+            uvs,
+            faces,
+            materials,
+            nodes: Vec::new(),
+        };
+        if !mesh.faces.is_empty() {
+            mesh.build_node(0, mesh.faces.len());
+        }
+        Ok(mesh)
+    }
+
+    pub fn from_indices(
+        positions: Vec<Point3>,
+        indices: &[[usize; 3]],
+        material: MaterialEnum,
+    ) -> Result<Self, MeshError> {
+        let faces = indices.iter().map(|&i| MeshFace::new(i)).collect();
+        Self::new(positions, Vec::new(), Vec::new(), faces, vec![material])
+    }
+
+    pub fn face_count(&self) -> usize {
+        self.faces.len()
+    }
+
+    fn vertices(&self, face: &MeshFace) -> (Point3, Point3, Point3) {
+        let [i0, i1, i2] = face.positions;
+        (self.positions[i0], self.positions[i1], self.positions[i2])
+    }
+
+    fn face_bounds(&self, face: &MeshFace) -> Aabb {
+        let (v0, v1, v2) = self.vertices(face);
+        triangle_bounds(v0, v1, v2)
+    }
+
+    fn build_node(&mut self, start: usize, count: usize) -> usize {
+        let bbox = self.faces[start..start + count]
+            .iter()
+            .fold(Aabb::empty(), |acc, face| {
+                Aabb::enclosing(&acc, &self.face_bounds(face))
+            });
+
+        let index = self.nodes.len();
+        self.nodes.push(MeshNode {
+            bbox,
+            start,
+            count,
+            left: 0,
+            right: 0,
+        });
+
+        if count <= MAX_LEAF_FACES {
+            return index;
+        }
+
+        let centroid_bounds =
+            self.faces[start..start + count]
+                .iter()
+                .fold(Aabb::empty(), |acc, face| {
+                    let c = self.face_bounds(face).centroid();
+                    Aabb::enclosing(&acc, &Aabb::from_points(c, c))
+                });
+        let axis = centroid_bounds.longest_axis();
+
+        let positions = &self.positions;
+        let centroid = |face: &MeshFace| {
+            let [i0, i1, i2] = face.positions;
+            (positions[i0][axis] + positions[i1][axis] + positions[i2][axis]) / 3.0
+        };
+        let mid = count / 2;
+        self.faces[start..start + count]
+            .select_nth_unstable_by(mid, |a, b| centroid(a).total_cmp(&centroid(b)));
+
+        let left = self.build_node(start, mid);
+        let right = self.build_node(start + mid, count - mid);
+        let node = &mut self.nodes[index];
+        node.count = 0;
+        node.left = left;
+        node.right = right;
+
+        index
+    }
+
+    fn fill_record<'a>(
+        &'a self,
+        r: &Ray,
+        face: &MeshFace,
+        t: f64,
+        b1: f64,
+        b2: f64,
+        rec: &mut HitRecord<'a>,
+    ) {
+        let (v0, v1, v2) = self.vertices(face);
+        let b0 = 1.0 - b1 - b2;
+
+        rec.t = t;
+        rec.p = r.at(t);
+
+        (rec.u, rec.v) = match face.uvs {
+            Some([i0, i1, i2]) => {
+                let (uv0, uv1, uv2) = (self.uvs[i0], self.uvs[i1], self.uvs[i2]);
+                (
+                    b0 * uv0[0] + b1 * uv1[0] + b2 * uv2[0],
+                    b0 * uv0[1] + b1 * uv1[1] + b2 * uv2[1],
+                )
+            }
+            None => (b1, b2),
+        };
+
+        let geometric_normal = Vec3::cross(v1 - v0, v2 - v0).unit_vector();
+        rec.set_face_normal(r, geometric_normal);
+
+        if let Some([i0, i1, i2]) = face.normals {
+            let mut shading_normal =
+                (b0 * self.normals[i0] + b1 * self.normals[i1] + b2 * self.normals[i2])
+                    .unit_vector();
+            if Vec3::dot(shading_normal, geometric_normal) < 0.0 {
+                shading_normal = -shading_normal;
+            }
+            rec.normal = match rec.front_face {
+                true => shading_normal,
+                false => -shading_normal,
+            };
+        }
+
+        rec.material = &self.materials[face.material];
+    }
+}
+
+impl Hittable for TriangleMesh {
+    fn hit<'a>(&'a self, r: &Ray, interval: Interval, rec: &mut HitRecord<'a>) -> bool {
+        if self.nodes.is_empty() {
+            return false;
+        }
+
+        let mut closest_so_far = interval.max();
+        let mut closest_hit = None;
+        let mut stack = [0; MAX_TRAVERSAL_DEPTH];
+        let mut stack_len = 1;
+
+        while stack_len > 0 {
+            stack_len -= 1;
+            let node = &self.nodes[stack[stack_len]];
+            if !node
+                .bbox
+                .hit(r, Interval::new(interval.min(), closest_so_far))
+            {
+                continue;
+            }
+
+            if node.count == 0 {
+                stack[stack_len] = node.right;
+                stack[stack_len + 1] = node.left;
+                stack_len += 2;
+                continue;
+            }
+
+            for face in &self.faces[node.start..node.start + node.count] {
+                let (v0, v1, v2) = self.vertices(face);
+                let ray_t = Interval::new(interval.min(), closest_so_far);
+                if let Some((t, b1, b2)) = intersect(r, v0, v1, v2, &ray_t) {
+                    closest_so_far = t;
+                    closest_hit = Some((face, t, b1, b2));
+                }
+            }
+        }
+
+        match closest_hit {
+            Some((face, t, b1, b2)) => {
+                self.fill_record(r, face, t, b1, b2, rec);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        match self.nodes.first() {
+            Some(root) => root.bbox.clone(),
+            None => Aabb::empty(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quad_mesh() -> TriangleMesh {
+        let positions = vec![
+            Point3::new(-1.0, -1.0, 0.0),
+            Point3::new(1.0, -1.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(-1.0, 1.0, 0.0),
+        ];
+        TriangleMesh::from_indices(positions, &[[0, 1, 2], [0, 2, 3]], MaterialEnum::default())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_hits_both_faces() {
+        let mesh = quad_mesh();
+        for (x, y) in [(0.5, -0.5), (-0.5, 0.5)] {
+            let r = Ray::new(Point3::new(x, y, 5.0), Vec3::new(0.0, 0.0, -1.0));
+            let mut rec = HitRecord::default();
+            assert!(mesh.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+            assert!((rec.t - 5.0).abs() < 1e-9);
+            assert!(rec.front_face);
+        }
+    }
+
+    #[test]
+    fn test_misses_outside() {
+        let mesh = quad_mesh();
+        let r = Ray::new(Point3::new(1.5, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0));
+        let mut rec = HitRecord::default();
+        assert!(!mesh.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+    }
+
+    #[test]
+    fn test_interpolates_vertex_normals() {
+        let positions = vec![
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+        ];
+        let normals = vec![
+            Vec3::new(0.0, 0.0, 1.0),
+            Vec3::new(1.0, 0.0, 1.0).unit_vector(),
+            Vec3::new(0.0, 0.0, 1.0),
+        ];
+        let face = MeshFace {
+            positions: [0, 1, 2],
+            normals: Some([0, 1, 2]),
+            uvs: None,
+            material: 0,
+        };
+        let mesh = TriangleMesh::new(
+            positions,
+            normals,
+            Vec::new(),
+            vec![face],
+            vec![MaterialEnum::default()],
+        )
+        .unwrap();
+
+        let r = Ray::new(Point3::new(0.5, 0.25, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        let mut rec = HitRecord::default();
+        assert!(mesh.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+        assert!(rec.normal.x() > 0.0);
+        assert!((rec.normal.length() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rejects_out_of_range_indices() {
+        let positions = vec![
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+        ];
+        let err = TriangleMesh::from_indices(
+            positions.clone(),
+            &[[0, 1, 2], [0, 2, 3]],
+            MaterialEnum::default(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!((err.face, err.missing), (1, "vertex"));
+
+        let face = MeshFace {
+            uvs: Some([0, 1, 2]),
+            ..MeshFace::new([0, 1, 2])
+        };
+        let err = TriangleMesh::new(
+            positions,
+            Vec::new(),
+            vec![[0.0, 0.0]; 2],
+            vec![face],
+            vec![MaterialEnum::default()],
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err.missing, "texture coordinate");
+    }
+
+    #[test]
+    fn test_deep_tree_traversal() {
+        let mut positions = Vec::new();
+        let mut indices = Vec::new();
+        for k in 0..4096 {
+            let x = k as f64;
+            positions.extend([
+                Point3::new(x, 0.0, 0.0),
+                Point3::new(x + 0.5, 0.0, 0.0),
+                Point3::new(x, 0.5, 0.0),
+            ]);
+            indices.push([3 * k, 3 * k + 1, 3 * k + 2]);
+        }
+        let mesh =
+            TriangleMesh::from_indices(positions, &indices, MaterialEnum::default()).unwrap();
+
+        let r = Ray::new(Point3::new(4000.1, 0.1, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        let mut rec = HitRecord::default();
+        assert!(mesh.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+        assert!((rec.p.x() - 4000.1).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,744 @@
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    colour::Colour,
+    hittable_list::HittableList,
+    material::{Dielectric, DiffuseLight, Lambertian, MaterialEnum, Metal},
+    mesh::{MeshFace, TriangleMesh},
+    ray::Point3,
+    texture::{ImageTexture, TextureEnum},
+    vec3::Vec3,
+};
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub file: PathBuf,
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.file.display(), self.line, self.message)
+    }
+}
+
+pub struct ObjImport {
+    pub object: HittableList,
+    pub groups: Vec<String>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+pub fn load_obj<P: AsRef<Path>>(path: P, default_material: MaterialEnum) -> io::Result<ObjImport> {
+    let path = path.as_ref();
+    let src = fs::read_to_string(path)?;
+
+    Ok(parse_obj(&src, path, default_material))
+}
+
+/// Parses OBJ source; `path` names the file in diagnostics and anchors `mtllib` lookups.
+pub fn parse_obj(src: &str, path: &Path, default_material: MaterialEnum) -> ObjImport {
+    let mut parser = ObjParser::new(path, default_material);
+    for (number, line) in src.lines().enumerate() {
+        parser.line = number + 1;
+        parser.parse_line(line);
+    }
+    parser.finish()
+}
+
+struct Group {
+    name: String,
+    faces: Vec<MeshFace>,
+}
+
+struct ObjParser {
+    file: PathBuf,
+    line: usize,
+    positions: Vec<Point3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<[f64; 2]>,
+    groups: Vec<Group>,
+    materials: Vec<MaterialEnum>,
+    library: HashMap<String, MaterialEnum>,
+    material_indices: HashMap<String, usize>,
+    current_material: usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl ObjParser {
+    fn new(path: &Path, default_material: MaterialEnum) -> Self {
+        Self {
+            file: path.to_path_buf(),
+            line: 0,
+            positions: Vec::new(),
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            groups: vec![Group {
+                name: String::from("default"),
+                faces: Vec::new(),
+            }],
+            materials: vec![default_material],
+            library: HashMap::new(),
+            material_indices: HashMap::new(),
+            current_material: 0,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn warn(&mut self, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic {
+            file: self.file.clone(),
+            line: self.line,
+            message: message.into(),
+        });
+    }
+
+    fn parse_line(&mut self, line: &str) {
+        let line = match line.find('#') {
+            Some(comment) => &line[..comment],
+            None => line,
+        };
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            return;
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        match keyword {
+            "v" => {
+                if let Some(v) = self.parse_floats::<3>(&args, 3, 4) {
+                    self.positions.push(Point3::new(v[0], v[1], v[2]));
+                }
+            }
+            "vn" => {
+                if let Some(n) = self.parse_floats::<3>(&args, 3, 3) {
+                    self.normals.push(Vec3::new(n[0], n[1], n[2]));
+                }
+            }
+            "vt" => {
+                if let Some(uv) = self.parse_floats::<2>(&args, 1, 3) {
+                    self.uvs.push(uv);
+                }
+            }
+            "f" => self.parse_face(&args),
+            "g" | "o" => {
+                let name = match args.is_empty() {
+                    true => String::from("default"),
+                    false => args.join(" "),
+                };
+                self.start_group(name);
+            }
+            "usemtl" => self.use_material(&args.join(" ")),
+            "mtllib" => {
+                for library in args {
+                    self.load_library(library);
+                }
+            }
+            "s" | "l" | "p" => {}
+            other => self.warn(format!("ignoring unsupported statement '{}'", other)),
+        }
+    }
+
+    fn parse_floats<const N: usize>(
+        &mut self,
+        args: &[&str],
+        min: usize,
+        max: usize,
+    ) -> Option<[f64; N]> {
+        if args.len() < min || args.len() > max {
+            self.warn(format!(
+                "expected {} to {} numbers, found {}",
+                min,
+                max,
+                args.len()
+            ));
+            return None;
+        }
+
+        let mut values = [0.0; N];
+        for (i, arg) in args.iter().enumerate().take(N) {
+            match arg.parse::<f64>() {
+                Ok(value) => values[i] = value,
+                Err(_) => {
+                    self.warn(format!("invalid number '{}'", arg));
+                    return None;
+                }
+            }
+        }
+
+        Some(values)
+    }
+
+    fn resolve_index(&mut self, token: &str, count: usize, kind: &str) -> Option<usize> {
+        let index = match token.parse::<i64>() {
+            Ok(index) => index,
+            Err(_) => {
+                self.warn(format!("invalid {} index '{}'", kind, token));
+                return None;
+            }
+        };
+
+        let resolved = match index {
+            i if i > 0 => i - 1,
+            i if i < 0 => count as i64 + i,
+            _ => -1,
+        };
+
+        match resolved >= 0 && (resolved as usize) < count {
+            true => Some(resolved as usize),
+            false => {
+                self.warn(format!("{} index {} is out of range", kind, index));
+                None
+            }
+        }
+    }
+
+    fn parse_face(&mut self, args: &[&str]) {
+        if args.len() < 3 {
+            self.warn(format!(
+                "face needs at least 3 vertices, found {}",
+                args.len()
+            ));
+            return;
+        }
+
+        let mut positions = Vec::with_capacity(args.len());
+        let mut uvs = Vec::with_capacity(args.len());
+        let mut normals = Vec::with_capacity(args.len());
+
+        for vertex in args {
+            let mut parts = vertex.split('/');
+            let position = parts.next().unwrap_or_default();
+            let uv = parts.next().filter(|s| !s.is_empty());
+            let normal = parts.next().filter(|s| !s.is_empty());
+
+            let Some(position) = self.resolve_index(position, self.positions.len(), "vertex")
+            else {
+                return;
+            };
+            positions.push(position);
+
+            if let Some(uv) = uv {
+                match self.resolve_index(uv, self.uvs.len(), "texture coordinate") {
+                    Some(uv) => uvs.push(uv),
+                    None => return,
+                }
+            }
+            if let Some(normal) = normal {
+                match self.resolve_index(normal, self.normals.len(), "normal") {
+                    Some(normal) => normals.push(normal),
+                    None => return,
+                }
+            }
+        }
+
+        let uvs = (uvs.len() == positions.len()).then_some(uvs);
+        let normals = (normals.len() == positions.len()).then_some(normals);
+
+        let points: Vec<Point3> = positions.iter().map(|&i| self.positions[i]).collect();
+        let mut triangles = triangulate(&points);
+        let count = triangles.len();
+        triangles.retain(|&[a, b, c]| {
+            Vec3::cross(points[b] - points[a], points[c] - points[a]).length_squared() > 0.0
+        });
+        if triangles.len() < count {
+            self.warn(format!(
+                "skipping {} zero-area triangle(s) of degenerate face",
+                count - triangles.len()
+            ));
+        }
+
+        let material = self.current_material;
+        let group = self.groups.last_mut().expect("parser always has a group");
+        for [a, b, c] in triangles {
+            group.faces.push(MeshFace {
+                positions: [positions[a], positions[b], positions[c]],
+                normals: normals.as_ref().map(|n| [n[a], n[b], n[c]]),
+                uvs: uvs.as_ref().map(|uv| [uv[a], uv[b], uv[c]]),
+                material,
+            });
+        }
+    }
+
+    fn start_group(&mut self, name: String) {
+        let current = self.groups.last_mut().expect("parser always has a group");
+        match current.faces.is_empty() {
+            true => current.name = name,
+            false => self.groups.push(Group {
+                name,
+                faces: Vec::new(),
+            }),
+        }
+    }
+
+    fn use_material(&mut self, name: &str) {
+        if let Some(&index) = self.material_indices.get(name) {
+            self.current_material = index;
+            return;
+        }
+
+        match self.library.get(name) {
+            Some(material) => {
+                self.materials.push(material.clone());
+                self.current_material = self.materials.len() - 1;
+                self.material_indices
+                    .insert(name.to_string(), self.current_material);
+            }
+            None => {
+                self.warn(format!("unknown material '{}', using default", name));
+                self.current_material = 0;
+            }
+        }
+    }
+
+    fn load_library(&mut self, name: &str) {
+        let path = match self.file.parent() {
+            Some(dir) => dir.join(name),
+            None => PathBuf::from(name),
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(src) => {
+                let (materials, diagnostics) = parse_mtl(&src, &path);
+                self.library.extend(materials);
+                self.diagnostics.extend(diagnostics);
+            }
+            Err(e) => self.warn(format!(
+                "could not read material library {}: {}",
+                path.display(),
+                e
+            )),
+        }
+    }
+
+    fn finish(self) -> ObjImport {
+        let mut object = HittableList::new();
+        let mut groups = Vec::new();
+
+        for group in self.groups {
+            if group.faces.is_empty() {
+                continue;
+            }
+
+            let (positions, normals, uvs, faces) =
+                compact(&self.positions, &self.normals, &self.uvs, group.faces);
+            let mesh = TriangleMesh::new(positions, normals, uvs, faces, self.materials.clone())
+                .expect("compacted faces index their own buffers");
+            object.add(Box::new(mesh));
+            groups.push(group.name);
+        }
+
+        ObjImport {
+            object,
+            groups,
+            diagnostics: self.diagnostics,
+        }
+    }
+}
+
+/// Copies only the attributes referenced by `faces` into group-local buffers.
+fn compact(
+    positions: &[Point3],
+    normals: &[Vec3],
+    uvs: &[[f64; 2]],
+    mut faces: Vec<MeshFace>,
+) -> (Vec<Point3>, Vec<Vec3>, Vec<[f64; 2]>, Vec<MeshFace>) {
+    fn remap<T: Copy>(
+        source: &[T],
+        target: &mut Vec<T>,
+        map: &mut HashMap<usize, usize>,
+        indices: &mut [usize; 3],
+    ) {
+        for index in indices.iter_mut() {
+            *index = *map.entry(*index).or_insert_with(|| {
+                target.push(source[*index]);
+                target.len() - 1
+            });
+        }
+    }
+
+    let (mut local_positions, mut local_normals, mut local_uvs) = (vec![], vec![], vec![]);
+    let (mut position_map, mut normal_map, mut uv_map) =
+        (HashMap::new(), HashMap::new(), HashMap::new());
+
+    for face in &mut faces {
+        remap(
+            positions,
+            &mut local_positions,
+            &mut position_map,
+            &mut face.positions,
+        );
+        if let Some(n) = face.normals.as_mut() {
+            remap(normals, &mut local_normals, &mut normal_map, n);
+        }
+        if let Some(uv) = face.uvs.as_mut() {
+            remap(uvs, &mut local_uvs, &mut uv_map, uv);
+        }
+    }
+
+    (local_positions, local_normals, local_uvs, faces)
+}
+
+/// Ear-clipping triangulation of a planar polygon, returning corner indices.
+fn triangulate(points: &[Point3]) -> Vec<[usize; 3]> {
+    if points.len() == 3 {
+        return vec![[0, 1, 2]];
+    }
+
+    // Newell's method gives a robust polygon normal for choosing the projection plane.
+    let mut normal = Vec3::default();
+    for (i, p) in points.iter().enumerate() {
+        let q = points[(i + 1) % points.len()];
+        normal += Vec3::new(
+            (p.y() - q.y()) * (p.z() + q.z()),
+            (p.z() - q.z()) * (p.x() + q.x()),
+            (p.x() - q.x()) * (p.y() + q.y()),
+        );
+    }
+
+    let drop_axis = match (normal.x().abs(), normal.y().abs(), normal.z().abs()) {
+        (x, y, z) if x >= y && x >= z => 0,
+        (_, y, z) if y >= z => 1,
+        _ => 2,
+    };
+    let (ax, ay) = match drop_axis {
+        0 => (1, 2),
+        1 => (2, 0),
+        _ => (0, 1),
+    };
+    let flat: Vec<(f64, f64)> = points.iter().map(|p| (p[ax], p[ay])).collect();
+    let orientation = normal[drop_axis].signum();
+
+    let cross = |a: usize, b: usize, c: usize| {
+        let (ax_, ay_) = flat[a];
+        let (bx, by) = flat[b];
+        let (cx, cy) = flat[c];
+        ((bx - ax_) * (cy - ay_) - (by - ay_) * (cx - ax_)) * orientation
+    };
+
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    let mut triangles = Vec::with_capacity(points.len() - 2);
+
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let ear = (0..n).find(|&i| {
+            let (a, b, c) = (
+                remaining[(i + n - 1) % n],
+                remaining[i],
+                remaining[(i + 1) % n],
+            );
+            if cross(a, b, c) <= 0.0 {
+                return false;
+            }
+            remaining.iter().all(|&p| {
+                p == a
+                    || p == b
+                    || p == c
+                    || cross(a, b, p) < 0.0
+                    || cross(b, c, p) < 0.0
+                    || cross(c, a, p) < 0.0
+            })
+        });
+
+        match ear {
+            Some(i) => {
+                triangles.push([
+                    remaining[(i + n - 1) % n],
+                    remaining[i],
+                    remaining[(i + 1) % n],
+                ]);
+                remaining.remove(i);
+            }
+            None => {
+                // Not a simple polygon in the projected plane; fall back to a fan.
+                for i in 1..remaining.len() - 1 {
+                    triangles.push([remaining[0], remaining[i], remaining[i + 1]]);
+                }
+                return triangles;
+            }
+        }
+    }
+
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+    triangles
+}
+
+#[derive(Default)]
+struct MtlEntry {
+    kd: Option<Colour>,
+    ks: Option<Colour>,
+    ke: Option<Colour>,
+    ns: Option<f64>,
+    ni: Option<f64>,
+    dissolve: Option<f64>,
+    map_kd: Option<PathBuf>,
+}
+
+impl MtlEntry {
+    fn into_material(
+        self,
+        diagnostics: &mut Vec<Diagnostic>,
+        file: &Path,
+        line: usize,
+    ) -> MaterialEnum {
+        let kd = self.kd.unwrap_or(Colour::new(0.8, 0.8, 0.8));
+        let ks = self.ks.unwrap_or_default();
+
+        if let Some(ke) = self.ke.filter(|ke| max_component(*ke) > 0.0) {
+            return MaterialEnum::DiffuseLight(DiffuseLight::new(ke));
+        }
+
+        if self.dissolve.is_some_and(|d| d < 1.0) {
+            return MaterialEnum::Dielectric(Dielectric::new(self.ni.unwrap_or(1.5)));
+        }
+
+        if max_component(ks) > max_component(kd) {
+            let shininess = self.ns.unwrap_or(0.0).max(0.0);
+            let fuzz = (2.0 / (shininess + 2.0)).sqrt();
+            return MaterialEnum::Metal(Metal::new(ks, fuzz));
+        }
+
+        let texture = match self.map_kd {
+            Some(path) => match ImageTexture::load(&path) {
+                Ok(image) => TextureEnum::Image(image),
+                Err(e) => {
+                    diagnostics.push(Diagnostic {
+                        file: file.to_path_buf(),
+                        line,
+                        message: format!("could not load texture {}: {}", path.display(), e),
+                    });
+                    TextureEnum::from(kd)
+                }
+            },
+            None => TextureEnum::from(kd),
+        };
+
+        MaterialEnum::Lambertian(Lambertian::from_texture(texture))
+    }
+}
+
+fn max_component(c: Colour) -> f64 {
+    c.x().max(c.y()).max(c.z())
+}
+
+/// Parses an MTL library, mapping each entry onto the closest `MaterialEnum` variant.
+pub fn parse_mtl(src: &str, path: &Path) -> (HashMap<String, MaterialEnum>, Vec<Diagnostic>) {
+    let mut materials = HashMap::new();
+    let mut diagnostics = Vec::new();
+    let mut current: Option<(String, usize, MtlEntry)> = None;
+    let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+    let warn = |diagnostics: &mut Vec<Diagnostic>, line: usize, message: String| {
+        diagnostics.push(Diagnostic {
+            file: path.to_path_buf(),
+            line,
+            message,
+        })
+    };
+
+    for (number, line) in src.lines().enumerate() {
+        let number = number + 1;
+        let line = match line.find('#') {
+            Some(comment) => &line[..comment],
+            None => line,
+        };
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        if keyword == "newmtl" {
+            if let Some((name, start, entry)) = current.take() {
+                let material = entry.into_material(&mut diagnostics, path, start);
+                materials.insert(name, material);
+            }
+            current = Some((args.join(" "), number, MtlEntry::default()));
+            continue;
+        }
+
+        let Some((_, _, entry)) = current.as_mut() else {
+            warn(
+                &mut diagnostics,
+                number,
+                format!("'{}' before any newmtl", keyword),
+            );
+            continue;
+        };
+
+        let numbers: Result<Vec<f64>, _> = args.iter().map(|a| a.parse::<f64>()).collect();
+        let colour = |values: &[f64]| match values {
+            [v] => Some(Colour::new(*v, *v, *v)),
+            [r, g, b, ..] => Some(Colour::new(*r, *g, *b)),
+            _ => None,
+        };
+
+        match (keyword, numbers) {
+            ("Kd" | "Ks" | "Ke", Ok(values)) => match colour(&values) {
+                Some(c) => match keyword {
+                    "Kd" => entry.kd = Some(c),
+                    "Ks" => entry.ks = Some(c),
+                    _ => entry.ke = Some(c),
+                },
+                None => warn(
+                    &mut diagnostics,
+                    number,
+                    format!("{} needs a colour", keyword),
+                ),
+            },
+            ("Ns" | "Ni" | "d" | "Tr", Ok(values)) if values.len() == 1 => match keyword {
+                "Ns" => entry.ns = Some(values[0]),
+                "Ni" => match values[0] {
+                    ni if ni > 0.0 && ni.is_finite() => entry.ni = Some(ni),
+                    ni => warn(
+                        &mut diagnostics,
+                        number,
+                        format!(
+                            "Ni must be finite and positive, got {}; using the default",
+                            ni
+                        ),
+                    ),
+                },
+                "d" => entry.dissolve = Some(values[0]),
+                _ => entry.dissolve = Some(1.0 - values[0]),
+            },
+            ("map_Kd", _) => match args.last() {
+                Some(file) => entry.map_kd = Some(base_dir.join(file)),
+                None => warn(
+                    &mut diagnostics,
+                    number,
+                    String::from("map_Kd needs a file"),
+                ),
+            },
+            ("Ka" | "illum" | "Tf", _) => {}
+            ("Kd" | "Ks" | "Ke" | "Ns" | "Ni" | "d" | "Tr", _) => warn(
+                &mut diagnostics,
+                number,
+                format!("invalid value for {}", keyword),
+            ),
+            (other, _) => warn(
+                &mut diagnostics,
+                number,
+                format!("ignoring unsupported statement '{}'", other),
+            ),
+        }
+    }
+
+    if let Some((name, start, entry)) = current.take() {
+        let material = entry.into_material(&mut diagnostics, path, start);
+        materials.insert(name, material);
+    }
+
+    (materials, diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hittable::HitRecord, hittable::Hittable, interval::Interval, ray::Ray};
+
+    #[test]
+    fn test_parses_groups_and_polygons() {
+        let src = "\
+v -1 -1 0
+v 1 -1 0
+v 1 1 0
+v -1 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+g quad
+f 1/1 2/2 3/3 4/4
+g tri
+f -4 -3 -2
+";
+        let import = parse_obj(src, Path::new("test.obj"), MaterialEnum::default());
+        assert!(import.diagnostics.is_empty());
+        assert_eq!(import.groups, vec!["quad", "tri"]);
+        assert_eq!(import.object.len(), 2);
+
+        let r = Ray::new(Point3::new(-0.5, 0.5, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        let mut rec = HitRecord::default();
+        assert!(import
+            .object
+            .hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+        assert!((rec.u - 0.25).abs() < 1e-9);
+        assert!((rec.v - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reports_bad_lines() {
+        let src = "v 0 0 0\nv 1 0 0\nv zero 1 0\nf 1 2 7\nusemtl missing\n";
+        let import = parse_obj(src, Path::new("bad.obj"), MaterialEnum::default());
+        let lines: Vec<usize> = import.diagnostics.iter().map(|d| d.line).collect();
+        assert_eq!(lines, vec![3, 4, 5]);
+        assert!(import.object.is_empty());
+    }
+
+    #[test]
+    fn test_skips_zero_area_triangles() {
+        let src = "v 0 0 0\nv 1 0 0\nv 2 0 0\nv 0 1 0\nf 1 2 3\nf 1 2 4\nf 1 2 2 4\n";
+        let import = parse_obj(src, Path::new("flat.obj"), MaterialEnum::default());
+        let lines: Vec<usize> = import.diagnostics.iter().map(|d| d.line).collect();
+        assert_eq!(lines, vec![5, 7]);
+
+        let r = Ray::new(Point3::new(0.2, 0.2, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        let mut rec = HitRecord::default();
+        assert!(import
+            .object
+            .hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+    }
+
+    #[test]
+    fn test_triangulates_concave_polygon() {
+        let points = [
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(2.0, 0.0, 0.0),
+            Point3::new(2.0, 2.0, 0.0),
+            Point3::new(1.0, 0.5, 0.0),
+            Point3::new(0.0, 2.0, 0.0),
+        ];
+        let triangles = triangulate(&points);
+        assert_eq!(triangles.len(), 3);
+
+        let area: f64 = triangles
+            .iter()
+            .map(|&[a, b, c]| 0.5 * Vec3::cross(points[b] - points[a], points[c] - points[a]).z())
+            .sum();
+        assert!((area - 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_maps_mtl_entries() {
+        let src = "\
+newmtl matte
+Kd 0.5 0.2 0.1
+newmtl mirror
+Kd 0 0 0
+Ks 0.9 0.9 0.9
+Ns 1000
+newmtl glass
+Ni 1.45
+d 0.1
+";
+        let (materials, diagnostics) = parse_mtl(src, Path::new("test.mtl"));
+        assert!(diagnostics.is_empty());
+        assert!(matches!(materials["matte"], MaterialEnum::Lambertian(_)));
+        assert!(matches!(materials["mirror"], MaterialEnum::Metal(_)));
+        assert!(matches!(materials["glass"], MaterialEnum::Dielectric(_)));
+    }
+
+    #[test]
+    fn test_warns_on_non_positive_refraction_index() {
+        let src = "newmtl glass\nNi 0\nd 0.5\n";
+        let (materials, diagnostics) = parse_mtl(src, Path::new("test.mtl"));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 2);
+        assert!(matches!(materials["glass"], MaterialEnum::Dielectric(_)));
+    }
+}
@@ -0,0 +1,274 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{framebuffer::Framebuffer, tonemap::DisplayTransform};
+
+#[derive(Debug)]
+pub enum OutputError {
+    Io(io::Error),
+    Encode(image::ImageError),
+    UnsupportedFormat(String),
+}
+
+impl fmt::Display for OutputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputError::Io(e) => write!(f, "i/o error: {}", e),
+            OutputError::Encode(e) => write!(f, "encoding error: {}", e),
+            OutputError::UnsupportedFormat(ext) => {
+                write!(f, "unsupported output format: '{}'", ext)
+            }
+        }
+    }
+}
+
+impl std::error::Error for OutputError {}
+
+impl From<io::Error> for OutputError {
+    fn from(e: io::Error) -> Self {
+        OutputError::Io(e)
+    }
+}
+
+impl From<image::ImageError> for OutputError {
+    fn from(e: image::ImageError) -> Self {
+        OutputError::Encode(e)
+    }
+}
+
+pub const SUPPORTED_FORMATS: &[&str] = &["ppm", "pfm", "png", "jpg", "jpeg", "exr", "hdr"];
+
+pub fn is_supported_format<P: AsRef<Path>>(path: P) -> bool {
+    SUPPORTED_FORMATS.contains(&extension_of(path.as_ref()).as_str())
+}
+
+fn extension_of(path: &Path) -> String {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+pub fn write_image<P: AsRef<Path>>(
+    fb: &Framebuffer,
+    path: P,
+    display: &DisplayTransform,
+) -> Result<(), OutputError> {
+    let path = path.as_ref();
+    let extension = extension_of(path);
+
+    match extension.as_str() {
+        "ppm" => write_ppm(fb, BufWriter::new(File::create(path)?), display),
+        "pfm" => write_pfm(fb, BufWriter::new(File::create(path)?)),
+        "png" => write_encoded(fb, path, image::ImageFormat::Png, display),
+        "jpg" | "jpeg" => write_encoded(fb, path, image::ImageFormat::Jpeg, display),
+        "exr" => write_encoded_hdr(fb, path, image::ImageFormat::OpenExr),
+        "hdr" => write_encoded_hdr(fb, path, image::ImageFormat::Hdr),
+        _ => Err(OutputError::UnsupportedFormat(extension)),
+    }
+}
+
+/// Like [`write_image`], but writes to a temporary file beside `path` and renames it
+/// into place, so readers never see a partly written image.
+pub fn replace_image<P: AsRef<Path>>(
+    fb: &Framebuffer,
+    path: P,
+    display: &DisplayTransform,
+) -> Result<(), OutputError> {
+    let path = path.as_ref();
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    // Keep the extension last, as it selects the format.
+    let temporary = path.with_file_name(format!(".partial.{}", file_name));
+
+    write_image(fb, &temporary, display)?;
+    std::fs::rename(&temporary, path)?;
+
+    Ok(())
+}
+
+pub fn write_ppm<W: Write>(
+    fb: &Framebuffer,
+    mut out: W,
+    display: &DisplayTransform,
+) -> Result<(), OutputError> {
+    write!(out, "P6\n{} {}\n255\n", fb.width(), fb.height())?;
+    out.write_all(&fb.to_rgb8(display))?;
+    out.flush()?;
+
+    Ok(())
+}
+
+pub fn write_pfm<W: Write>(fb: &Framebuffer, mut out: W) -> Result<(), OutputError> {
+    // PFM stores scanlines bottom-to-top; a negative scale marks little-endian data.
+    write!(out, "PF\n{} {}\n-1.0\n", fb.width(), fb.height())?;
+    for j in (0..fb.height()).rev() {
+        for i in 0..fb.width() {
+            let pixel = fb.get(i, j);
+            for component in [pixel.x(), pixel.y(), pixel.z()] {
+                out.write_all(&(component as f32).to_le_bytes())?;
+            }
+        }
+    }
+    out.flush()?;
+
+    Ok(())
+}
+
+fn write_encoded(
+    fb: &Framebuffer,
+    path: &Path,
+    format: image::ImageFormat,
+    display: &DisplayTransform,
+) -> Result<(), OutputError> {
+    image::save_buffer_with_format(
+        path,
+        &fb.to_rgb8(display),
+        fb.width() as u32,
+        fb.height() as u32,
+        image::ExtendedColorType::Rgb8,
+        format,
+    )?;
+
+    Ok(())
+}
+
+fn write_encoded_hdr(
+    fb: &Framebuffer,
+    path: &Path,
+    format: image::ImageFormat,
+) -> Result<(), OutputError> {
+    let buffer =
+        image::Rgb32FImage::from_raw(fb.width() as u32, fb.height() as u32, fb.to_rgb32f())
+            .ok_or_else(|| {
+                image::ImageError::Parameter(image::error::ParameterError::from_kind(
+                    image::error::ParameterErrorKind::DimensionMismatch,
+                ))
+            })?;
+    buffer.save_with_format(path, format)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{colour::Colour, tonemap::ToneMapper};
+
+    fn gradient() -> Framebuffer {
+        let mut fb = Framebuffer::new(5, 3);
+        for j in 0..fb.height() {
+            for i in 0..fb.width() {
+                fb.set(i, j, Colour::new(i as f64 * 0.6, j as f64 * 0.25, 0.125));
+            }
+        }
+        fb
+    }
+
+    fn temporary_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("ray-tracing-{}-{}", std::process::id(), name))
+    }
+
+    fn round_trip(fb: &Framebuffer, name: &str) -> image::DynamicImage {
+        let path = temporary_path(name);
+        write_image(fb, &path, &DisplayTransform::new(ToneMapper::Clamp, 0.0)).unwrap();
+        let decoded = image::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        decoded
+    }
+
+    #[test]
+    fn test_png_round_trip() {
+        let fb = gradient();
+        let decoded = round_trip(&fb, "round-trip.png").to_rgb8();
+        assert_eq!(decoded.dimensions(), (5, 3));
+        let display = DisplayTransform::new(ToneMapper::Clamp, 0.0);
+        assert_eq!(decoded.into_raw(), fb.to_rgb8(&display));
+    }
+
+    #[test]
+    fn test_hdr_round_trip() {
+        let fb = gradient();
+        let decoded = round_trip(&fb, "round-trip.hdr").to_rgb32f();
+        assert_eq!(decoded.dimensions(), (5, 3));
+        // RGBE shares one exponent per pixel, keeping about 1% precision.
+        for (a, b) in decoded.into_raw().iter().zip(fb.to_rgb32f()) {
+            assert!((a - b).abs() <= 0.01 * b.max(0.125), "{} != {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_exr_round_trip() {
+        let fb = gradient();
+        let decoded = round_trip(&fb, "round-trip.exr").to_rgb32f();
+        assert_eq!(decoded.dimensions(), (5, 3));
+        assert_eq!(decoded.into_raw(), fb.to_rgb32f());
+    }
+
+    #[test]
+    fn test_ppm_header_and_size() {
+        let fb = gradient();
+        let mut out = Vec::new();
+        write_ppm(
+            &fb,
+            &mut out,
+            &DisplayTransform::new(ToneMapper::Clamp, 0.0),
+        )
+        .unwrap();
+
+        let header = b"P6\n5 3\n255\n";
+        assert!(out.starts_with(header));
+        assert_eq!(out.len(), header.len() + 5 * 3 * 3);
+    }
+
+    #[test]
+    fn test_pfm_stores_rows_bottom_up() {
+        let fb = gradient();
+        let mut out = Vec::new();
+        write_pfm(&fb, &mut out).unwrap();
+
+        let header = b"PF\n5 3\n-1.0\n";
+        assert!(out.starts_with(header));
+        assert_eq!(out.len(), header.len() + 5 * 3 * 3 * 4);
+        // The first stored pixel is the bottom-left one, whose green channel is 0.5.
+        let green = f32::from_le_bytes(out[header.len() + 4..header.len() + 8].try_into().unwrap());
+        assert_eq!(green, 0.5);
+    }
+
+    #[test]
+    fn test_jpeg_keeps_dimensions() {
+        let decoded = round_trip(&gradient(), "dimensions.JPG");
+        assert_eq!((decoded.width(), decoded.height()), (5, 3));
+    }
+
+    #[test]
+    fn test_rejects_unknown_extension() {
+        let path = temporary_path("image.bmp");
+        let display = DisplayTransform::new(ToneMapper::Clamp, 0.0);
+        assert!(!is_supported_format(&path));
+        assert!(matches!(
+            write_image(&gradient(), &path, &display),
+            Err(OutputError::UnsupportedFormat(ext)) if ext == "bmp"
+        ));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_replace_image_leaves_no_partial_file() {
+        let path = temporary_path("replace.png");
+        let display = DisplayTransform::new(ToneMapper::Clamp, 0.0);
+        replace_image(&gradient(), &path, &display).unwrap();
+        replace_image(&Framebuffer::new(2, 2), &path, &display).unwrap();
+
+        assert_eq!(image::image_dimensions(&path).unwrap(), (2, 2));
+        let partial = path.with_file_name(format!(
+            ".partial.{}",
+            path.file_name().unwrap().to_string_lossy()
+        ));
+        assert!(!partial.exists());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
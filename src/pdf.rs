@@ -0,0 +1,88 @@
+use std::f64::consts::PI;
+
+use crate::{
+    ray::Ray,
+    vec3::{cosine_direction, tangent_frame, uniform_sphere_direction, Vec3},
+};
+
+/// The distribution a material draws its scattered direction from.
+#[derive(Clone, Copy, Default)]
+pub enum ScatterPdf {
+    /// A delta distribution holding the only ray the material can scatter into.
+    Delta(Ray),
+    /// Cosine-weighted about the given unit normal.
+    Cosine(Vec3),
+    /// Uniform over all directions.
+    #[default]
+    Uniform,
+}
+
+impl ScatterPdf {
+    /// Solid-angle density of generating `direction`; zero for delta distributions.
+    pub fn value(&self, direction: Vec3) -> f64 {
+        match self {
+            ScatterPdf::Delta(_) => 0.0,
+            ScatterPdf::Cosine(normal) => {
+                let cos_theta = Vec3::dot(direction.unit_vector(), *normal);
+                cos_theta.max(0.0) / PI
+            }
+            ScatterPdf::Uniform => 1.0 / (4.0 * PI),
+        }
+    }
+
+    /// Maps the uniform sample `u` in [0, 1)² to a direction from this distribution.
+    pub fn generate(&self, u: (f64, f64)) -> Vec3 {
+        match self {
+            ScatterPdf::Delta(r) => r.direction(),
+            ScatterPdf::Cosine(normal) => {
+                let (t, b) = tangent_frame(*normal);
+                let d = cosine_direction(u);
+                d.x() * t + d.y() * b + d.z() * *normal
+            }
+            ScatterPdf::Uniform => uniform_sphere_direction(u),
+        }
+    }
+}
+
+/// Multiple importance sampling weight for a sample drawn with density `pdf`
+/// when `other_pdf` could also have produced it.
+pub fn power_heuristic(pdf: f64, other_pdf: f64) -> f64 {
+    let (a, b) = (pdf * pdf, other_pdf * other_pdf);
+    match a + b > 0.0 {
+        true => a / (a + b),
+        false => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{random_double, RenderRng};
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_cosine_pdf_matches_its_samples() {
+        let mut rng = RenderRng::seed_from_u64(3);
+        let normal = Vec3::new(1.0, 2.0, -0.5).unit_vector();
+        let pdf = ScatterPdf::Cosine(normal);
+
+        // Under a cosine-weighted density the mean cosine is 2/3.
+        let n = 100_000;
+        let mut total = 0.0;
+        for _ in 0..n {
+            let direction = pdf.generate((random_double(&mut rng), random_double(&mut rng)));
+            let cos_theta = Vec3::dot(direction.unit_vector(), normal);
+            assert!(cos_theta >= 0.0);
+            assert!((pdf.value(direction) - cos_theta / PI).abs() < 1e-12);
+            total += cos_theta;
+        }
+        assert!((total / n as f64 - 2.0 / 3.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_power_heuristic_weights_sum_to_one() {
+        let (a, b) = (0.3, 1.7);
+        assert!((power_heuristic(a, b) + power_heuristic(b, a) - 1.0).abs() < 1e-12);
+        assert_eq!(power_heuristic(0.5, 0.0), 1.0);
+    }
+}
@@ -0,0 +1,141 @@
+use rand::SeedableRng;
+
+use crate::{
+    ray::Point3,
+    utils::{random_int_in_range, RenderRng},
+    vec3::Vec3,
+};
+
+const POINT_COUNT: usize = 256;
+
+/// Seed for [`Perlin::new`], so noise textures look the same on every run.
+const DEFAULT_SEED: u64 = 0x5eed;
+
+pub struct Perlin {
+    randvec: Vec<Vec3>,
+    perm_x: Vec<usize>,
+    perm_y: Vec<usize>,
+    perm_z: Vec<usize>,
+}
+
+impl Perlin {
+    pub fn new() -> Self {
+        Self::from_rng(&mut RenderRng::seed_from_u64(DEFAULT_SEED))
+    }
+
+    pub fn from_rng(rng: &mut RenderRng) -> Self {
+        let randvec = (0..POINT_COUNT)
+            .map(|_| Vec3::random_in_range(rng, -1.0, 1.0).unit_vector())
+            .collect();
+
+        Self {
+            randvec,
+            perm_x: Perlin::generate_perm(rng),
+            perm_y: Perlin::generate_perm(rng),
+            perm_z: Perlin::generate_perm(rng),
+        }
+    }
+
+    pub fn noise(&self, p: Point3) -> f64 {
+        let u = p.x() - p.x().floor();
+        let v = p.y() - p.y().floor();
+        let w = p.z() - p.z().floor();
+
+        let i = p.x().floor() as i64;
+        let j = p.y().floor() as i64;
+        let k = p.z().floor() as i64;
+
+        let mut c = [[[Vec3::default(); 2]; 2]; 2];
+        for (di, plane) in c.iter_mut().enumerate() {
+            for (dj, row) in plane.iter_mut().enumerate() {
+                for (dk, corner) in row.iter_mut().enumerate() {
+                    *corner = self.randvec[self.perm_x[Perlin::wrap(i + di as i64)]
+                        ^ self.perm_y[Perlin::wrap(j + dj as i64)]
+                        ^ self.perm_z[Perlin::wrap(k + dk as i64)]];
+                }
+            }
+        }
+
+        Perlin::interp(&c, u, v, w)
+    }
+
+    pub fn turb(&self, p: Point3, depth: usize) -> f64 {
+        let mut accum = 0.0;
+        let mut temp_p = p;
+        let mut weight = 1.0;
+
+        for _ in 0..depth {
+            accum += weight * self.noise(temp_p);
+            weight *= 0.5;
+            temp_p *= 2.0;
+        }
+
+        accum.abs()
+    }
+
+    fn wrap(i: i64) -> usize {
+        (i & (POINT_COUNT as i64 - 1)) as usize
+    }
+
+    fn generate_perm(rng: &mut RenderRng) -> Vec<usize> {
+        let mut p: Vec<usize> = (0..POINT_COUNT).collect();
+
+        for i in (1..POINT_COUNT).rev() {
+            let target = random_int_in_range(rng, 0, i as i64) as usize;
+            p.swap(i, target);
+        }
+
+        p
+    }
+
+    fn interp(c: &[[[Vec3; 2]; 2]; 2], u: f64, v: f64, w: f64) -> f64 {
+        let uu = u * u * (3.0 - 2.0 * u);
+        let vv = v * v * (3.0 - 2.0 * v);
+        let ww = w * w * (3.0 - 2.0 * w);
+        let mut accum = 0.0;
+
+        for (i, plane) in c.iter().enumerate() {
+            for (j, row) in plane.iter().enumerate() {
+                for (k, corner) in row.iter().enumerate() {
+                    let (fi, fj, fk) = (i as f64, j as f64, k as f64);
+                    let weight_v = Vec3::new(u - fi, v - fj, w - fk);
+                    accum += (fi * uu + (1.0 - fi) * (1.0 - uu))
+                        * (fj * vv + (1.0 - fj) * (1.0 - vv))
+                        * (fk * ww + (1.0 - fk) * (1.0 - ww))
+                        * Vec3::dot(*corner, weight_v);
+                }
+            }
+        }
+
+        accum
+    }
+}
+
+impl Default for Perlin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noise_is_bounded() {
+        let perlin = Perlin::new();
+        let mut rng = RenderRng::seed_from_u64(1);
+        for _ in 0..1000 {
+            let p = Vec3::random_in_range(&mut rng, -50.0, 50.0);
+            let n = perlin.noise(p);
+            assert!((-1.0..=1.0).contains(&n));
+            assert!(perlin.turb(p, 7) >= 0.0);
+        }
+    }
+
+    #[test]
+    fn test_noise_vanishes_on_lattice() {
+        let perlin = Perlin::new();
+        assert_eq!(perlin.noise(Point3::new(3.0, -2.0, 7.0)), 0.0);
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{area_pdf_to_solid_angle, HitRecord, Hittable},
+    interval::Interval,
+    material::MaterialEnum,
+    ray::{Point3, Ray},
+    vec3::{tangent_frame, Vec3},
+};
+
+fn plane_intersection(
+    r: &Ray,
+    point: Point3,
+    normal: Vec3,
+    interval: &Interval,
+) -> Option<(f64, Point3)> {
+    let denom = Vec3::dot(normal, r.direction());
+    if denom.abs() < 1e-8 {
+        return None;
+    }
+
+    let t = Vec3::dot(point - r.origin(), normal) / denom;
+    match interval.surrounds(t) {
+        true => Some((t, r.at(t))),
+        false => None,
+    }
+}
+
+pub struct Plane {
+    point: Point3,
+    normal: Vec3,
+    tangent: Vec3,
+    bitangent: Vec3,
+    material: MaterialEnum,
+}
+
+impl Plane {
+    pub fn new(point: Point3, normal: Vec3, material: MaterialEnum) -> Self {
+        let normal = normal.unit_vector();
+        let (tangent, bitangent) = tangent_frame(normal);
+        Self {
+            point,
+            normal,
+            tangent,
+            bitangent,
+            material,
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn hit<'a>(&'a self, r: &Ray, interval: Interval, rec: &mut HitRecord<'a>) -> bool {
+        let Some((t, p)) = plane_intersection(r, self.point, self.normal, &interval) else {
+            return false;
+        };
+
+        let offset = p - self.point;
+        rec.t = t;
+        rec.p = p;
+        rec.u = Vec3::dot(offset, self.tangent);
+        rec.v = Vec3::dot(offset, self.bitangent);
+        rec.material = &self.material;
+        rec.set_face_normal(r, self.normal);
+
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::new(
+            Interval::universe(),
+            Interval::universe(),
+            Interval::universe(),
+        )
+    }
+}
+
+pub struct Disk {
+    center: Point3,
+    normal: Vec3,
+    radius: f64,
+    tangent: Vec3,
+    bitangent: Vec3,
+    material: MaterialEnum,
+    bbox: Aabb,
+}
+
+impl Disk {
+    pub fn new(center: Point3, normal: Vec3, radius: f64, material: MaterialEnum) -> Self {
+        let normal = normal.unit_vector();
+        let (tangent, bitangent) = tangent_frame(normal);
+
+        // Each axis extends by the radius scaled by how far the disk tilts out of that axis.
+        let extent = Vec3::new(
+            radius * (1.0 - normal.x() * normal.x()).max(0.0).sqrt(),
+            radius * (1.0 - normal.y() * normal.y()).max(0.0).sqrt(),
+            radius * (1.0 - normal.z() * normal.z()).max(0.0).sqrt(),
+        );
+
+        Self {
+            center,
+            normal,
+            radius,
+            tangent,
+            bitangent,
+            material,
+            bbox: Aabb::from_points(center - extent, center + extent),
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hit<'a>(&'a self, r: &Ray, interval: Interval, rec: &mut HitRecord<'a>) -> bool {
+        let Some((t, p)) = plane_intersection(r, self.center, self.normal, &interval) else {
+            return false;
+        };
+
+        let offset = p - self.center;
+        if offset.length_squared() > self.radius * self.radius {
+            return false;
+        }
+
+        let x = Vec3::dot(offset, self.tangent);
+        let y = Vec3::dot(offset, self.bitangent);
+
+        rec.t = t;
+        rec.p = p;
+        rec.u = (y.atan2(x) + std::f64::consts::PI) / (2.0 * std::f64::consts::PI);
+        rec.v = offset.length() / self.radius;
+        rec.material = &self.material;
+        rec.set_face_normal(r, self.normal);
+
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox.clone()
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        let mut rec = HitRecord::default();
+        if !self.hit(
+            &Ray::new(origin, direction),
+            Interval::new(0.001, f64::INFINITY),
+            &mut rec,
+        ) {
+            return 0.0;
+        }
+
+        let area = std::f64::consts::PI * self.radius * self.radius;
+        area_pdf_to_solid_angle(direction, &rec, area)
+    }
+
+    fn random(&self, origin: Point3, u: (f64, f64)) -> Vec3 {
+        let r = self.radius * u.0.sqrt();
+        let phi = 2.0 * std::f64::consts::PI * u.1;
+        let p = self.center + r * phi.cos() * self.tangent + r * phi.sin() * self.bitangent;
+        p - origin
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(object: &dyn Hittable, origin: Point3, direction: Vec3) -> Option<(f64, f64, f64)> {
+        let mut rec = HitRecord::default();
+        object
+            .hit(
+                &Ray::new(origin, direction),
+                Interval::new(0.001, f64::INFINITY),
+                &mut rec,
+            )
+            .then_some((rec.t, rec.u, rec.v))
+    }
+
+    #[test]
+    fn test_plane_hit_reports_tangent_coordinates() {
+        let normal = Vec3::new(0.0, 0.0, 1.0);
+        let plane = Plane::new(Point3::new(0.0, 0.0, 1.0), normal, MaterialEnum::default());
+        let (tangent, bitangent) = tangent_frame(normal);
+
+        let origin = Point3::new(3.0, -2.0, 5.0);
+        let (t, u, v) = hit(&plane, origin, Vec3::new(0.0, 0.0, -2.0)).unwrap();
+        let offset = Vec3::new(3.0, -2.0, 0.0);
+        assert!((t - 2.0).abs() < 1e-12);
+        assert!((u - Vec3::dot(offset, tangent)).abs() < 1e-12);
+        assert!((v - Vec3::dot(offset, bitangent)).abs() < 1e-12);
+
+        assert!(hit(&plane, origin, Vec3::new(1.0, 1.0, 0.0)).is_none());
+        assert!(hit(&plane, origin, Vec3::new(0.0, 0.0, 1.0)).is_none());
+        assert!(!plane.bounding_box().is_bounded());
+    }
+
+    #[test]
+    fn test_disk_hit_is_limited_to_its_radius() {
+        let disk = Disk::new(
+            Point3::default(),
+            Vec3::new(0.0, 1.0, 0.0),
+            2.0,
+            MaterialEnum::default(),
+        );
+        let down = Vec3::new(0.0, -1.0, 0.0);
+
+        let (t, u, v) = hit(&disk, Point3::new(1.0, 4.0, 0.0), down).unwrap();
+        assert!((t - 4.0).abs() < 1e-12);
+        assert!((0.0..=1.0).contains(&u));
+        assert!((v - 0.5).abs() < 1e-12);
+
+        assert!(hit(&disk, Point3::new(1.5, 4.0, 1.5), down).is_none());
+        assert!(hit(&disk, Point3::new(0.0, 4.0, 0.0), Vec3::new(1.0, 0.0, 0.0)).is_none());
+
+        let bbox = disk.bounding_box();
+        assert!(bbox.is_bounded());
+        assert!((bbox.x.max() - 2.0).abs() < 1e-3 && (bbox.z.min() + 2.0).abs() < 1e-3);
+    }
+}
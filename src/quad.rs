@@ -0,0 +1,192 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{area_pdf_to_solid_angle, HitRecord, Hittable},
+    hittable_list::HittableList,
+    interval::Interval,
+    material::MaterialEnum,
+    ray::{Point3, Ray},
+    vec3::Vec3,
+};
+
+pub struct Quad {
+    q: Point3,
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+    normal: Vec3,
+    d: f64,
+    area: f64,
+    material: MaterialEnum,
+    bbox: Aabb,
+}
+
+impl Quad {
+    pub fn new(q: Point3, u: Vec3, v: Vec3, material: MaterialEnum) -> Self {
+        let n = Vec3::cross(u, v);
+        let normal = n.unit_vector();
+
+        let bbox_diagonal1 = Aabb::from_points(q, q + u + v);
+        let bbox_diagonal2 = Aabb::from_points(q + u, q + v);
+
+        Self {
+            q,
+            u,
+            v,
+            w: n / Vec3::dot(n, n),
+            normal,
+            d: Vec3::dot(normal, q),
+            area: n.length(),
+            material,
+            bbox: Aabb::enclosing(&bbox_diagonal1, &bbox_diagonal2),
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit<'a>(&'a self, r: &Ray, interval: Interval, rec: &mut HitRecord<'a>) -> bool {
+        let denom = Vec3::dot(self.normal, r.direction());
+        if denom.abs() < 1e-8 {
+            return false;
+        }
+
+        let t = (self.d - Vec3::dot(self.normal, r.origin())) / denom;
+        if !interval.surrounds(t) {
+            return false;
+        }
+
+        let intersection = r.at(t);
+        let planar_hitpt_vector = intersection - self.q;
+        let alpha = Vec3::dot(self.w, Vec3::cross(planar_hitpt_vector, self.v));
+        let beta = Vec3::dot(self.w, Vec3::cross(self.u, planar_hitpt_vector));
+
+        let unit_interval = Interval::new(0.0, 1.0);
+        if !unit_interval.contains(alpha) || !unit_interval.contains(beta) {
+            return false;
+        }
+
+        rec.t = t;
+        rec.p = intersection;
+        rec.u = alpha;
+        rec.v = beta;
+        rec.material = &self.material;
+        rec.set_face_normal(r, self.normal);
+
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox.clone()
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        let mut rec = HitRecord::default();
+        if !self.hit(
+            &Ray::new(origin, direction),
+            Interval::new(0.001, f64::INFINITY),
+            &mut rec,
+        ) {
+            return 0.0;
+        }
+
+        area_pdf_to_solid_angle(direction, &rec, self.area)
+    }
+
+    fn random(&self, origin: Point3, u: (f64, f64)) -> Vec3 {
+        let p = self.q + (u.0 * self.u) + (u.1 * self.v);
+        p - origin
+    }
+}
+
+/// Returns the six quads enclosing the box with opposite corners `a` and `b`.
+pub fn make_box(a: Point3, b: Point3, material: MaterialEnum) -> HittableList {
+    let mut sides = HittableList::new();
+
+    let min = Point3::new(a.x().min(b.x()), a.y().min(b.y()), a.z().min(b.z()));
+    let max = Point3::new(a.x().max(b.x()), a.y().max(b.y()), a.z().max(b.z()));
+
+    let dx = Vec3::new(max.x() - min.x(), 0.0, 0.0);
+    let dy = Vec3::new(0.0, max.y() - min.y(), 0.0);
+    let dz = Vec3::new(0.0, 0.0, max.z() - min.z());
+
+    let faces = [
+        (Point3::new(min.x(), min.y(), max.z()), dx, dy),
+        (Point3::new(max.x(), min.y(), max.z()), -dz, dy),
+        (Point3::new(max.x(), min.y(), min.z()), -dx, dy),
+        (Point3::new(min.x(), min.y(), min.z()), dz, dy),
+        (Point3::new(min.x(), max.y(), max.z()), dx, -dz),
+        (Point3::new(min.x(), min.y(), min.z()), dx, dz),
+    ];
+    for (q, u, v) in faces {
+        sides.add(Box::new(Quad::new(q, u, v, material.clone())));
+    }
+
+    sides
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(object: &dyn Hittable, origin: Point3, direction: Vec3) -> Option<(f64, f64, f64)> {
+        let mut rec = HitRecord::default();
+        object
+            .hit(
+                &Ray::new(origin, direction),
+                Interval::new(0.001, f64::INFINITY),
+                &mut rec,
+            )
+            .then_some((rec.t, rec.u, rec.v))
+    }
+
+    #[test]
+    fn test_quad_hit_reports_parametric_coordinates() {
+        let quad = Quad::new(
+            Point3::default(),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 4.0, 0.0),
+            MaterialEnum::default(),
+        );
+        let down = Vec3::new(0.0, 0.0, -1.0);
+
+        let (t, u, v) = hit(&quad, Point3::new(0.5, 3.0, 2.0), down).unwrap();
+        assert!((t - 2.0).abs() < 1e-12);
+        assert!((u - 0.25).abs() < 1e-12);
+        assert!((v - 0.75).abs() < 1e-12);
+
+        assert!(hit(&quad, Point3::new(2.5, 1.0, 2.0), down).is_none());
+        assert!(hit(&quad, Point3::new(1.0, -0.5, 2.0), down).is_none());
+        assert!(hit(&quad, Point3::new(1.0, 1.0, 2.0), Vec3::new(1.0, 0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn test_make_box_encloses_the_corners() {
+        let sides = make_box(
+            Point3::new(1.0, 1.0, 1.0),
+            Point3::new(-1.0, -1.0, -1.0),
+            MaterialEnum::default(),
+        );
+        let bbox = sides.bounding_box();
+        assert!((bbox.x.min() + 1.0).abs() < 1e-3 && (bbox.x.max() - 1.0).abs() < 1e-3);
+
+        // Every axis-aligned ray from outside meets the nearest face one unit early.
+        for direction in [
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(-1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, -1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+            Vec3::new(0.0, 0.0, -1.0),
+        ] {
+            let (t, ..) = hit(&sides, -3.0 * direction, direction).unwrap();
+            assert!((t - 2.0).abs() < 1e-12);
+        }
+
+        assert!(hit(
+            &sides,
+            Point3::new(2.0, 0.0, -3.0),
+            Vec3::new(0.0, 0.0, 1.0)
+        )
+        .is_none());
+        assert_eq!(sides.into_objects().len(), 6);
+    }
+}
@@ -1,13 +1,4 @@
-use std::f64::INFINITY;
-
-use crate::{
-    colour::Colour,
-    hittable::{HitRecord, Hittable},
-    hittable_list::HittableList,
-    interval::Interval,
-    material::Material,
-    vec3::Vec3,
-};
+use crate::vec3::Vec3;
 
 pub type Point3 = Vec3;
 
@@ -15,11 +6,20 @@ pub type Point3 = Vec3;
 pub struct Ray {
     origin: Point3,
     direction: Vec3,
+    time: f64,
 }
 
 impl Ray {
     pub fn new(origin: Point3, direction: Vec3) -> Self {
-        Self { origin, direction }
+        Self::with_time(origin, direction, 0.0)
+    }
+
+    pub fn with_time(origin: Point3, direction: Vec3, time: f64) -> Self {
+        Self {
+            origin,
+            direction,
+            time,
+        }
     }
 
     pub fn origin(&self) -> Point3 {
@@ -30,6 +30,10 @@ impl Ray {
         self.direction
     }
 
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
     pub fn at(&self, t: f64) -> Point3 {
         self.origin + t * self.direction
     }
@@ -47,28 +51,4 @@ impl Ray {
 
         (h - discriminant.sqrt()) / a
     }
-
-    pub fn colour(&self, world: &HittableList, depth: i64) -> Colour {
-        if depth <= 0 {
-            return Colour::new(0.0, 0.0, 0.0);
-        }
-
-        let mut rec = HitRecord::default();
-        if world.hit(self, Interval::new(0.001, INFINITY), &mut rec) {
-            let mut scattered = Ray::default();
-            let mut attenuation = Colour::default();
-            if rec
-                .material
-                .scatter(self, &rec, &mut attenuation, &mut scattered)
-            {
-                return attenuation * scattered.colour(world, depth - 1);
-            }
-
-            return Colour::default();
-        }
-
-        let unit_direction = self.direction().unit_vector();
-        let a = 0.5 * (unit_direction.y() + 1.0);
-        (1.0 - a) * Colour::new(1.0, 1.0, 1.0) + a * Colour::new(0.5, 0.7, 1.0)
-    }
 }
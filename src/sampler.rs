@@ -0,0 +1,470 @@
+use std::sync::OnceLock;
+
+use rand::SeedableRng;
+
+use crate::utils::{mix64, random_double, stream_rng, RenderRng};
+
+/// The largest `f64` below one, so samples always stay in [0, 1).
+const ONE_MINUS_EPSILON: f64 = 1.0 - f64::EPSILON / 2.0;
+
+/// Where each sample dimension of a pixel comes from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SamplerKind {
+    /// Independent uniform random numbers.
+    #[default]
+    Independent,
+    /// Jittered strata, shuffled independently per dimension. Samples past
+    /// `samples_per_pixel` have no strata left and are drawn independently.
+    Stratified,
+    /// The Halton sequence with its digits Owen-scrambled per pixel.
+    Halton,
+    /// Owen-scrambled Sobol points, padded so every dimension pair is a (0, 2)-sequence.
+    Sobol,
+    /// Sobol points dithered across pixels by a blue-noise mask, so the remaining error
+    /// shows up as high-frequency noise rather than blotches.
+    BlueNoise,
+}
+
+/// Supplies the sample values for one pixel sample at a time. Values are a pure
+/// function of the seed, pixel, sample index and dimension order.
+#[derive(Clone, Debug)]
+pub struct Sampler {
+    kind: SamplerKind,
+    samples_per_pixel: u32,
+    seed: u64,
+    pixel: (u32, u32),
+    sample_index: u32,
+    dimension: u32,
+    rng: RenderRng,
+}
+
+impl Sampler {
+    pub fn new(kind: SamplerKind, samples_per_pixel: i64, seed: u64) -> Self {
+        Self {
+            kind,
+            samples_per_pixel: samples_per_pixel.clamp(1, u32::MAX as i64) as u32,
+            seed,
+            pixel: (0, 0),
+            sample_index: 0,
+            dimension: 0,
+            rng: RenderRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn kind(&self) -> SamplerKind {
+        self.kind
+    }
+
+    /// Begins sample `index` of pixel (`i`, `j`); dimensions restart from zero.
+    /// Every kind accepts indices past `samples_per_pixel`, but only the Sobol-based
+    /// and Halton sequences stay well distributed there.
+    pub fn start_pixel_sample(&mut self, i: usize, j: usize, index: usize) {
+        self.pixel = (i as u32, j as u32);
+        self.sample_index = index as u32;
+        self.dimension = 0;
+        self.rng = stream_rng(
+            self.seed,
+            mix64(((j as u64) << 32 | i as u64) ^ mix64(index as u64)),
+        );
+    }
+
+    /// Generator for decisions that are not worth a sample dimension of their own.
+    pub fn rng(&mut self) -> &mut RenderRng {
+        &mut self.rng
+    }
+
+    pub fn get_1d(&mut self) -> f64 {
+        let dimension = self.dimension;
+        self.dimension += 1;
+        let hash = self.hash(dimension);
+
+        let u = match self.kind {
+            SamplerKind::Independent => random_double(&mut self.rng),
+            SamplerKind::Stratified => {
+                let n = self.samples_per_pixel;
+                match self.sample_index < n {
+                    true => {
+                        let stratum = permutation_element(self.sample_index, n, hash as u32);
+                        (stratum as f64 + random_double(&mut self.rng)) / n as f64
+                    }
+                    false => random_double(&mut self.rng),
+                }
+            }
+            SamplerKind::Halton => self.halton(dimension, hash),
+            SamplerKind::Sobol => {
+                let index = owen_scramble(self.sample_index, hash as u32);
+                sobol_sample(index, 0, (hash >> 32) as u32)
+            }
+            SamplerKind::BlueNoise => self.blue_noise(dimension, 0),
+        };
+
+        u.min(ONE_MINUS_EPSILON)
+    }
+
+    pub fn get_2d(&mut self) -> (f64, f64) {
+        let dimension = self.dimension;
+        self.dimension += 2;
+        let hash = self.hash(dimension);
+
+        let (u, v) = match self.kind {
+            SamplerKind::Independent => {
+                (random_double(&mut self.rng), random_double(&mut self.rng))
+            }
+            SamplerKind::Stratified => self.stratified_2d(hash),
+            SamplerKind::Halton => (
+                self.halton(dimension, hash),
+                self.halton(dimension + 1, mix64(hash)),
+            ),
+            SamplerKind::Sobol => {
+                // Shuffling the index within aligned power-of-two blocks decorrelates
+                // dimension pairs while keeping any prefix of the sequence well spread.
+                let index = owen_scramble(self.sample_index, hash as u32);
+                (
+                    sobol_sample(index, 0, (hash >> 32) as u32),
+                    sobol_sample(index, 1, mix64(hash) as u32),
+                )
+            }
+            SamplerKind::BlueNoise => {
+                (self.blue_noise(dimension, 0), self.blue_noise(dimension, 1))
+            }
+        };
+
+        (u.min(ONE_MINUS_EPSILON), v.min(ONE_MINUS_EPSILON))
+    }
+
+    fn hash(&self, dimension: u32) -> u64 {
+        let pixel = (self.pixel.1 as u64) << 32 | self.pixel.0 as u64;
+        mix64(self.seed ^ mix64(pixel ^ mix64(dimension as u64)))
+    }
+
+    /// Jittered point in a shuffled cell of a square grid when `samples_per_pixel` is a
+    /// perfect square. Other counts cannot tile a grid, so each axis is stratified on
+    /// its own instead (Latin hypercube), which keeps the density uniform.
+    fn stratified_2d(&mut self, hash: u64) -> (f64, f64) {
+        let n = self.samples_per_pixel;
+        if self.sample_index >= n {
+            return (random_double(&mut self.rng), random_double(&mut self.rng));
+        }
+
+        let (jx, jy) = (random_double(&mut self.rng), random_double(&mut self.rng));
+        let side = (n as f64).sqrt() as u32;
+        match side * side == n {
+            true => {
+                let cell = permutation_element(self.sample_index, n, hash as u32);
+                (
+                    ((cell % side) as f64 + jx) / side as f64,
+                    ((cell / side) as f64 + jy) / side as f64,
+                )
+            }
+            false => {
+                let x = permutation_element(self.sample_index, n, hash as u32);
+                let y = permutation_element(self.sample_index, n, (hash >> 32) as u32);
+                ((x as f64 + jx) / n as f64, (y as f64 + jy) / n as f64)
+            }
+        }
+    }
+
+    /// Owen-scrambled Halton coordinate; dimensions past the prime table fall back to
+    /// independent samples.
+    fn halton(&mut self, dimension: u32, hash: u64) -> f64 {
+        match PRIMES.get(dimension as usize) {
+            Some(&base) => scrambled_radical_inverse(base, self.sample_index as u64, hash),
+            None => random_double(&mut self.rng),
+        }
+    }
+
+    /// One coordinate of a Sobol point shared by every pixel, shifted by the mask value
+    /// at this pixel. Neighbouring pixels get decorrelated shifts, so their errors cancel
+    /// when the image is viewed at a distance.
+    fn blue_noise(&self, dimension: u32, axis: u32) -> f64 {
+        let dimension_hash = mix64(self.seed ^ mix64(dimension as u64 * 2 + axis as u64));
+        let base = sobol_sample(self.sample_index, axis, dimension_hash as u32);
+
+        let size = BLUE_NOISE_SIZE as u32;
+        let x = (self.pixel.0 + (dimension_hash >> 32) as u32 % size) % size;
+        let y = (self.pixel.1 + (dimension_hash >> 48) as u32 % size) % size;
+        let offset = blue_noise_mask()[(y * size + x) as usize];
+
+        (base + offset).fract()
+    }
+}
+
+const PRIMES: [u64; 64] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
+    197, 199, 211, 223, 227, 229, 233, 239, 241, 251, 257, 263, 269, 271, 277, 281, 283, 293, 307,
+    311,
+];
+
+/// Mirrors the base-`base` digits of `index` about the radix point, permuting each
+/// digit according to `hash` and the digits before it. Unscrambled, the large bases
+/// leave the first few hundred points of a dimension bunched near zero.
+fn scrambled_radical_inverse(base: u64, mut index: u64, hash: u64) -> f64 {
+    let inv_base = 1.0 / base as f64;
+    let mut inv_base_n = 1.0;
+    let mut reversed = 0u64;
+    // Keep going past the last digit of `index`, as its leading zeros are scrambled too.
+    while 1.0 - inv_base_n < 1.0 {
+        let next = index / base;
+        let digit = index - next * base;
+        let digit_hash = mix64(hash ^ reversed) as u32;
+        let digit = permutation_element(digit as u32, base as u32, digit_hash) as u64;
+        reversed = reversed * base + digit;
+        inv_base_n *= inv_base;
+        index = next;
+    }
+
+    (reversed as f64 * inv_base_n).min(ONE_MINUS_EPSILON)
+}
+
+/// Element `i` of a pseudo-random permutation of `0..n` selected by `seed`
+/// (Kensler, "Correlated Multi-Jittered Sampling").
+fn permutation_element(mut i: u32, n: u32, seed: u32) -> u32 {
+    let mut w = n.wrapping_sub(1);
+    w |= w >> 1;
+    w |= w >> 2;
+    w |= w >> 4;
+    w |= w >> 8;
+    w |= w >> 16;
+
+    loop {
+        i ^= seed;
+        i = i.wrapping_mul(0xe170_893d);
+        i ^= seed >> 16;
+        i ^= (i & w) >> 4;
+        i ^= seed >> 8;
+        i = i.wrapping_mul(0x0929_eb3f);
+        i ^= seed >> 23;
+        i ^= (i & w) >> 1;
+        i = i.wrapping_mul(1 | seed >> 27);
+        i = i.wrapping_mul(0x6935_fa69);
+        i ^= (i & w) >> 11;
+        i = i.wrapping_mul(0x74dc_b303);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0x9e50_1cc3);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0xc860_a3df);
+        i &= w;
+        i ^= i >> 5;
+        if i < n {
+            break;
+        }
+    }
+
+    (i.wrapping_add(seed)) % n
+}
+
+/// Coordinate `dimension` (0 or 1) of the `index`th Sobol point, Owen-scrambled by `seed`.
+fn sobol_sample(index: u32, dimension: u32, seed: u32) -> f64 {
+    let bits = match dimension {
+        0 => index.reverse_bits(),
+        _ => {
+            // Direction numbers for the primitive polynomial x + 1.
+            let mut bits = 0;
+            let mut direction = 1u32 << 31;
+            let mut index = index;
+            while index > 0 {
+                if index & 1 == 1 {
+                    bits ^= direction;
+                }
+                direction ^= direction >> 1;
+                index >>= 1;
+            }
+            bits
+        }
+    };
+
+    (owen_scramble(bits, seed) as f64 / (1u64 << 32) as f64).min(ONE_MINUS_EPSILON)
+}
+
+/// Hash-based Owen scrambling: each bit is flipped depending only on the bits above it,
+/// which preserves the stratification of the points.
+fn owen_scramble(mut v: u32, seed: u32) -> u32 {
+    v = v.reverse_bits();
+    v ^= v.wrapping_mul(0x3d20_adea);
+    v = v.wrapping_add(seed);
+    v = v.wrapping_mul((seed >> 16) | 1);
+    v ^= v.wrapping_mul(0x0552_6c56);
+    v ^= v.wrapping_mul(0x53a2_2864);
+    v.reverse_bits()
+}
+
+const BLUE_NOISE_SIZE: usize = 64;
+
+/// A tileable mask whose values are a permutation of evenly spaced levels in (0, 1)
+/// arranged with blue-noise spectrum, built once on first use.
+fn blue_noise_mask() -> &'static [f64] {
+    static MASK: OnceLock<Vec<f64>> = OnceLock::new();
+    MASK.get_or_init(generate_blue_noise_mask)
+}
+
+/// Void-and-cluster style ranking: each cell is filled in turn at the largest void,
+/// found as the empty cell with the least Gaussian energy from those already filled.
+fn generate_blue_noise_mask() -> Vec<f64> {
+    let n = BLUE_NOISE_SIZE;
+    let cells = n * n;
+    let two_sigma_squared = 2.0 * 1.5 * 1.5;
+
+    let kernel: Vec<f64> = (0..cells)
+        .map(|k| {
+            let (dx, dy) = (k % n, k / n);
+            let (dx, dy) = (dx.min(n - dx), dy.min(n - dy));
+            (-((dx * dx + dy * dy) as f64) / two_sigma_squared).exp()
+        })
+        .collect();
+
+    // A little seeded jitter breaks the ties a symmetric energy field would produce.
+    let mut rng = RenderRng::seed_from_u64(0xb1_0e);
+    let mut energy: Vec<f64> = (0..cells).map(|_| 1e-9 * random_double(&mut rng)).collect();
+    let mut rank = vec![usize::MAX; cells];
+
+    for r in 0..cells {
+        let next = (0..cells)
+            .filter(|&c| rank[c] == usize::MAX)
+            .min_by(|&a, &b| energy[a].total_cmp(&energy[b]))
+            .expect("an empty cell remains until every rank is assigned");
+        rank[next] = r;
+
+        let (px, py) = (next % n, next / n);
+        for (c, e) in energy.iter_mut().enumerate() {
+            let dx = (c % n + n - px) % n;
+            let dy = (c / n + n - py) % n;
+            *e += kernel[dy * n + dx];
+        }
+    }
+
+    rank.into_iter()
+        .map(|r| (r as f64 + 0.5) / cells as f64)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KINDS: [SamplerKind; 5] = [
+        SamplerKind::Independent,
+        SamplerKind::Stratified,
+        SamplerKind::Halton,
+        SamplerKind::Sobol,
+        SamplerKind::BlueNoise,
+    ];
+
+    fn first_2d(kind: SamplerKind, n: usize) -> Vec<(f64, f64)> {
+        let mut sampler = Sampler::new(kind, n as i64, 1);
+        (0..n)
+            .map(|s| {
+                sampler.start_pixel_sample(3, 5, s);
+                sampler.get_2d()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_samples_are_in_unit_interval_and_repeatable() {
+        for kind in KINDS {
+            let mut a = Sampler::new(kind, 16, 7);
+            let mut b = Sampler::new(kind, 16, 7);
+            for s in 0..16 {
+                a.start_pixel_sample(10, 20, s);
+                b.start_pixel_sample(10, 20, s);
+                for _ in 0..100 {
+                    let u = a.get_1d();
+                    assert!((0.0..1.0).contains(&u));
+                    assert_eq!(u, b.get_1d());
+                    let (x, y) = a.get_2d();
+                    assert!((0.0..1.0).contains(&x) && (0.0..1.0).contains(&y));
+                    assert_eq!((x, y), b.get_2d());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_stratified_and_sobol_fill_every_stratum() {
+        for kind in [SamplerKind::Stratified, SamplerKind::Sobol] {
+            let points = first_2d(kind, 16);
+            let mut cells = [0; 16];
+            for (x, y) in points {
+                cells[(y * 4.0) as usize * 4 + (x * 4.0) as usize] += 1;
+            }
+            assert_eq!(cells, [1; 16], "{:?}", kind);
+        }
+
+        // Counts that are not perfect squares are stratified along each axis.
+        for n in [5, 50] {
+            let mut columns = vec![0; n];
+            let mut rows = vec![0; n];
+            for (x, y) in first_2d(SamplerKind::Stratified, n) {
+                columns[(x * n as f64) as usize] += 1;
+                rows[(y * n as f64) as usize] += 1;
+            }
+            assert_eq!(columns, vec![1; n], "n = {}", n);
+            assert_eq!(rows, vec![1; n], "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn test_stratified_density_is_uniform_for_non_square_counts() {
+        // With five samples a 2 x 3 grid would leave the top-right cell empty.
+        let mut sampler = Sampler::new(SamplerKind::Stratified, 5, 1);
+        let (mut inside, mut total) = (0, 0);
+        for pixel in 0..4000 {
+            for s in 0..5 {
+                sampler.start_pixel_sample(pixel, 0, s);
+                let (x, y) = sampler.get_2d();
+                inside += (x >= 0.5 && y >= 2.0 / 3.0) as usize;
+                total += 1;
+            }
+        }
+        let fraction = inside as f64 / total as f64;
+        assert!((fraction - 1.0 / 6.0).abs() < 0.01, "{}", fraction);
+    }
+
+    #[test]
+    fn test_sobol_stays_stratified_past_samples_per_pixel() {
+        let mut sampler = Sampler::new(SamplerKind::Sobol, 4, 3);
+        let mut columns = [0; 64];
+        for s in 0..64 {
+            sampler.start_pixel_sample(1, 2, s);
+            columns[(sampler.get_2d().0 * 64.0) as usize] += 1;
+        }
+        assert_eq!(columns, [1; 64]);
+    }
+
+    #[test]
+    fn test_sobol_points_form_a_net() {
+        let points = first_2d(SamplerKind::Sobol, 16);
+        let mut columns = [0; 16];
+        let mut rows = [0; 16];
+        for (x, y) in points {
+            columns[(x * 16.0) as usize] += 1;
+            rows[(y * 16.0) as usize] += 1;
+        }
+        assert_eq!(columns, [1; 16]);
+        assert_eq!(rows, [1; 16]);
+    }
+
+    #[test]
+    fn test_scrambled_radical_inverse_stays_stratified() {
+        for base in [2, 3, 5] {
+            let n = base * base;
+            let mut strata = vec![0; n as usize];
+            for index in 0..n {
+                let u = scrambled_radical_inverse(base, index, 42);
+                strata[(u * n as f64) as usize] += 1;
+            }
+            assert_eq!(strata, vec![1; n as usize], "base {}", base);
+        }
+    }
+
+    #[test]
+    fn test_blue_noise_mask_is_a_permutation_of_levels() {
+        let mut mask = blue_noise_mask().to_vec();
+        mask.sort_by(f64::total_cmp);
+        let cells = mask.len() as f64;
+        for (r, value) in mask.iter().enumerate() {
+            assert!((value - (r as f64 + 0.5) / cells).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,835 @@
+use std::{collections::HashMap, fmt, fs, path::Path};
+
+use serde::Deserialize;
+use toml::Spanned;
+
+use crate::{
+    background::Background,
+    camera::{AdaptiveSampling, Camera},
+    constant_medium::ConstantMedium,
+    hittable::Hittable,
+    hittable_list::HittableList,
+    material::{Dielectric, DiffuseLight, Isotropic, Lambertian, MaterialEnum, Metal},
+    obj::{load_obj, Diagnostic},
+    plane::{Disk, Plane},
+    quad::{make_box, Quad},
+    sampler::SamplerKind,
+    sphere::Sphere,
+    triangle::Triangle,
+    vec3::Vec3,
+};
+
+pub struct Scene {
+    pub camera: Camera,
+    pub world: HittableList,
+    /// Emitters to sample directly; each must also be part of `world`.
+    pub lights: HittableList,
+    /// Problems in imported assets that did not stop the scene from loading.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug)]
+pub struct SceneError {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl SceneError {
+    fn at(src: &str, span: std::ops::Range<usize>, message: impl Into<String>) -> Self {
+        Self {
+            line: Some(line_of(src, span.start)),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+fn line_of(src: &str, offset: usize) -> usize {
+    src[..offset.min(src.len())].matches('\n').count() + 1
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneFile {
+    #[serde(default)]
+    camera: Option<Spanned<CameraDef>>,
+    #[serde(default)]
+    materials: HashMap<String, Spanned<MaterialDef>>,
+    #[serde(default)]
+    objects: Vec<Spanned<ObjectDef>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, default)]
+struct CameraDef {
+    aspect_ratio: f64,
+    image_width: i64,
+    samples_per_pixel: i64,
+    max_depth: i64,
+    russian_roulette_depth: i64,
+    vfov: i64,
+    look_from: [f64; 3],
+    look_at: [f64; 3],
+    vup: [f64; 3],
+    defocus_angle: f64,
+    focus_dist: f64,
+    shutter_open: f64,
+    shutter_close: f64,
+    sampler: SamplerDef,
+    adaptive: Option<AdaptiveDef>,
+    background: Option<BackgroundDef>,
+}
+
+impl Default for CameraDef {
+    fn default() -> Self {
+        Self {
+            aspect_ratio: 16.0 / 9.0,
+            image_width: 400,
+            samples_per_pixel: 100,
+            max_depth: 50,
+            russian_roulette_depth: 3,
+            vfov: 90,
+            look_from: [0.0, 0.0, 0.0],
+            look_at: [0.0, 0.0, -1.0],
+            vup: [0.0, 1.0, 0.0],
+            defocus_angle: 0.0,
+            focus_dist: 10.0,
+            shutter_open: 0.0,
+            shutter_close: 0.0,
+            sampler: SamplerDef::Independent,
+            adaptive: None,
+            background: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AdaptiveDef {
+    threshold: f64,
+    #[serde(default = "default_min_samples")]
+    min_samples: i64,
+}
+
+fn default_min_samples() -> i64 {
+    AdaptiveSampling::DEFAULT_MIN_SAMPLES
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SamplerDef {
+    Independent,
+    Stratified,
+    Halton,
+    Sobol,
+    BlueNoise,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum BackgroundDef {
+    Black,
+    Solid { colour: [f64; 3] },
+    Gradient { bottom: [f64; 3], top: [f64; 3] },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum MaterialDef {
+    Lambertian { albedo: [f64; 3] },
+    Metal { albedo: [f64; 3], fuzz: f64 },
+    Dielectric { refraction_index: f64 },
+    DiffuseLight { emit: [f64; 3] },
+    Isotropic { albedo: [f64; 3] },
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum ObjectDef {
+    Sphere {
+        center: [f64; 3],
+        radius: f64,
+        material: String,
+        #[serde(default)]
+        velocity: Option<[f64; 3]>,
+    },
+    Triangle {
+        vertices: [[f64; 3]; 3],
+        material: String,
+    },
+    Quad {
+        corner: [f64; 3],
+        u: [f64; 3],
+        v: [f64; 3],
+        material: String,
+    },
+    Plane {
+        point: [f64; 3],
+        normal: [f64; 3],
+        material: String,
+    },
+    Disk {
+        center: [f64; 3],
+        normal: [f64; 3],
+        radius: f64,
+        material: String,
+    },
+    Box {
+        min: [f64; 3],
+        max: [f64; 3],
+        material: String,
+    },
+    /// Triangles imported from a Wavefront OBJ file, resolved relative to the scene
+    /// file. `material` is used for faces without an MTL material of their own.
+    Obj { path: String, material: String },
+    /// A constant-density volume filling `boundary`, using the boundary's material
+    /// as its phase function.
+    Medium {
+        density: f64,
+        boundary: Box<ObjectDef>,
+    },
+}
+
+impl ObjectDef {
+    fn material(&self) -> &str {
+        match self {
+            ObjectDef::Sphere { material, .. }
+            | ObjectDef::Triangle { material, .. }
+            | ObjectDef::Quad { material, .. }
+            | ObjectDef::Plane { material, .. }
+            | ObjectDef::Disk { material, .. }
+            | ObjectDef::Box { material, .. }
+            | ObjectDef::Obj { material, .. } => material,
+            ObjectDef::Medium { boundary, .. } => boundary.material(),
+        }
+    }
+
+    /// Whether the built object can be sampled as a light; planes are unbounded,
+    /// media have no surface to sample and meshes cannot sample their faces.
+    fn is_sampleable(&self) -> bool {
+        !matches!(
+            self,
+            ObjectDef::Plane { .. } | ObjectDef::Medium { .. } | ObjectDef::Obj { .. }
+        )
+    }
+}
+
+fn vec3(v: [f64; 3]) -> Vec3 {
+    Vec3::new(v[0], v[1], v[2])
+}
+
+pub fn load_scene<P: AsRef<Path>>(path: P) -> Result<Scene, SceneError> {
+    let path = path.as_ref();
+    let src = fs::read_to_string(path).map_err(|e| SceneError {
+        line: None,
+        message: format!("could not read scene file: {}", e),
+    })?;
+
+    parse_scene_in(&src, path.parent().unwrap_or(Path::new("")))
+}
+
+/// Parses a scene, resolving the files it refers to against the current directory.
+pub fn parse_scene(src: &str) -> Result<Scene, SceneError> {
+    parse_scene_in(src, Path::new(""))
+}
+
+fn parse_scene_in(src: &str, base_dir: &Path) -> Result<Scene, SceneError> {
+    let file: SceneFile = toml::from_str(src).map_err(|e| SceneError {
+        line: e.span().map(|span| line_of(src, span.start)),
+        message: e.message().to_string(),
+    })?;
+
+    let camera = build_camera(
+        src,
+        file.camera
+            .unwrap_or_else(|| Spanned::new(0..0, CameraDef::default())),
+    )?;
+
+    let mut materials = HashMap::new();
+    for (name, def) in file.materials {
+        materials.insert(name, build_material(src, def)?);
+    }
+
+    let mut world = HittableList::new();
+    let mut lights = HittableList::new();
+    let mut diagnostics = Vec::new();
+    let mut assets = Assets {
+        base_dir,
+        diagnostics: &mut diagnostics,
+    };
+    for object in file.objects {
+        let span = object.span();
+        let def = object.into_inner();
+        let emissive = matches!(
+            materials.get(def.material()),
+            Some(MaterialEnum::DiffuseLight(_))
+        );
+        if emissive && def.is_sampleable() {
+            lights.add(build_object(
+                src,
+                &materials,
+                &mut assets,
+                def.clone(),
+                span.clone(),
+            )?);
+        }
+        world.add(build_object(src, &materials, &mut assets, def, span)?);
+    }
+
+    Ok(Scene {
+        camera,
+        world,
+        lights,
+        diagnostics,
+    })
+}
+
+/// Where imported files are found, and where problems reading them are collected.
+struct Assets<'a> {
+    base_dir: &'a Path,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+fn build_object(
+    src: &str,
+    materials: &HashMap<String, MaterialEnum>,
+    assets: &mut Assets,
+    def: ObjectDef,
+    span: std::ops::Range<usize>,
+) -> Result<Box<dyn Hittable>, SceneError> {
+    let object: Box<dyn Hittable> = match def {
+        ObjectDef::Sphere {
+            center,
+            radius,
+            material,
+            velocity,
+        } => {
+            if radius == 0.0 || !radius.is_finite() {
+                return Err(SceneError::at(
+                    src,
+                    span,
+                    format!("sphere radius must be finite and non-zero, got {}", radius),
+                ));
+            }
+            let material = lookup_material(src, materials, &material, span)?;
+            let sphere = match velocity {
+                Some(velocity) => Sphere::moving(
+                    vec3(center),
+                    vec3(center) + vec3(velocity),
+                    radius,
+                    material,
+                ),
+                None => Sphere::new(vec3(center), radius, material),
+            };
+            Box::new(sphere)
+        }
+        ObjectDef::Triangle { vertices, material } => {
+            let material = lookup_material(src, materials, &material, span)?;
+            Box::new(Triangle::new(
+                vec3(vertices[0]),
+                vec3(vertices[1]),
+                vec3(vertices[2]),
+                material,
+            ))
+        }
+        ObjectDef::Quad {
+            corner,
+            u,
+            v,
+            material,
+        } => {
+            if Vec3::cross(vec3(u), vec3(v)).length_squared() == 0.0 {
+                return Err(SceneError::at(
+                    src,
+                    span,
+                    "quad edges u and v must be non-zero and not parallel",
+                ));
+            }
+            let material = lookup_material(src, materials, &material, span)?;
+            Box::new(Quad::new(vec3(corner), vec3(u), vec3(v), material))
+        }
+        ObjectDef::Plane {
+            point,
+            normal,
+            material,
+        } => {
+            if normal == [0.0; 3] {
+                return Err(SceneError::at(src, span, "plane normal must be non-zero"));
+            }
+            let material = lookup_material(src, materials, &material, span)?;
+            Box::new(Plane::new(vec3(point), vec3(normal), material))
+        }
+        ObjectDef::Disk {
+            center,
+            normal,
+            radius,
+            material,
+        } => {
+            if radius <= 0.0 || !radius.is_finite() {
+                return Err(SceneError::at(
+                    src,
+                    span,
+                    format!("disk radius must be finite and positive, got {}", radius),
+                ));
+            }
+            if normal == [0.0; 3] {
+                return Err(SceneError::at(src, span, "disk normal must be non-zero"));
+            }
+            let material = lookup_material(src, materials, &material, span)?;
+            Box::new(Disk::new(vec3(center), vec3(normal), radius, material))
+        }
+        ObjectDef::Box { min, max, material } => {
+            let material = lookup_material(src, materials, &material, span)?;
+            Box::new(make_box(vec3(min), vec3(max), material))
+        }
+        ObjectDef::Obj { path, material } => {
+            let material = lookup_material(src, materials, &material, span.clone())?;
+            let path = assets.base_dir.join(path);
+            let import = load_obj(&path, material).map_err(|e| {
+                SceneError::at(
+                    src,
+                    span,
+                    format!("could not read OBJ file {}: {}", path.display(), e),
+                )
+            })?;
+            assets.diagnostics.extend(import.diagnostics);
+            Box::new(import.object)
+        }
+        ObjectDef::Medium { density, boundary } => {
+            if density <= 0.0 || !density.is_finite() {
+                return Err(SceneError::at(
+                    src,
+                    span,
+                    format!(
+                        "medium density must be finite and positive, got {}",
+                        density
+                    ),
+                ));
+            }
+            if matches!(*boundary, ObjectDef::Medium { .. }) {
+                return Err(SceneError::at(src, span, "medium boundary must be a shape"));
+            }
+            let phase_function =
+                lookup_material(src, materials, boundary.material(), span.clone())?;
+            if !matches!(phase_function, MaterialEnum::Isotropic(_)) {
+                return Err(SceneError::at(
+                    src,
+                    span,
+                    format!(
+                        "medium material '{}' must be isotropic",
+                        boundary.material()
+                    ),
+                ));
+            }
+            let boundary = build_object(src, materials, assets, *boundary, span)?;
+            Box::new(ConstantMedium::with_phase_function(
+                boundary,
+                density,
+                phase_function,
+            ))
+        }
+    };
+
+    Ok(object)
+}
+
+fn lookup_material(
+    src: &str,
+    materials: &HashMap<String, MaterialEnum>,
+    name: &str,
+    span: std::ops::Range<usize>,
+) -> Result<MaterialEnum, SceneError> {
+    materials
+        .get(name)
+        .cloned()
+        .ok_or_else(|| SceneError::at(src, span, format!("unknown material '{}'", name)))
+}
+
+fn build_camera(src: &str, def: Spanned<CameraDef>) -> Result<Camera, SceneError> {
+    let span = def.span();
+    let def = def.into_inner();
+
+    if def.aspect_ratio <= 0.0 || !def.aspect_ratio.is_finite() {
+        return Err(SceneError::at(
+            src,
+            span,
+            "aspect_ratio must be finite and positive",
+        ));
+    }
+    if def.image_width < 1 {
+        return Err(SceneError::at(src, span, "image_width must be at least 1"));
+    }
+    if def.samples_per_pixel < 1 {
+        return Err(SceneError::at(
+            src,
+            span,
+            "samples_per_pixel must be at least 1",
+        ));
+    }
+    if def.max_depth < 1 {
+        return Err(SceneError::at(src, span, "max_depth must be at least 1"));
+    }
+    if def.vfov <= 0 || def.vfov >= 180 {
+        return Err(SceneError::at(
+            src,
+            span,
+            "vfov must be between 0 and 180 degrees",
+        ));
+    }
+    if def.russian_roulette_depth < 0 {
+        return Err(SceneError::at(
+            src,
+            span,
+            "russian_roulette_depth must not be negative",
+        ));
+    }
+    if def.shutter_close < def.shutter_open {
+        return Err(SceneError::at(
+            src,
+            span,
+            "shutter_close must not precede shutter_open",
+        ));
+    }
+    // Moving objects are only defined, and bounded, over times 0 to 1.
+    if def.shutter_open < 0.0 || def.shutter_close > 1.0 {
+        return Err(SceneError::at(
+            src,
+            span,
+            "shutter_open and shutter_close must lie within [0, 1]",
+        ));
+    }
+    if let Some(adaptive) = &def.adaptive {
+        if adaptive.threshold <= 0.0 || !adaptive.threshold.is_finite() {
+            return Err(SceneError::at(
+                src,
+                span,
+                "adaptive threshold must be finite and positive",
+            ));
+        }
+        if adaptive.min_samples < 1 {
+            return Err(SceneError::at(
+                src,
+                span,
+                "adaptive min_samples must be at least 1",
+            ));
+        }
+    }
+    if def.look_from == def.look_at {
+        return Err(SceneError::at(
+            src,
+            span,
+            "look_from and look_at must differ",
+        ));
+    }
+    if Vec3::cross(vec3(def.vup), vec3(def.look_from) - vec3(def.look_at)).length_squared() == 0.0 {
+        return Err(SceneError::at(
+            src,
+            span,
+            "vup must not be parallel to the view direction",
+        ));
+    }
+    if def.focus_dist <= 0.0 || !def.focus_dist.is_finite() {
+        return Err(SceneError::at(
+            src,
+            span,
+            "focus_dist must be finite and positive",
+        ));
+    }
+
+    let mut camera = Camera::new(
+        def.aspect_ratio,
+        def.image_width,
+        def.samples_per_pixel,
+        def.max_depth,
+        def.vfov,
+        vec3(def.look_from),
+        vec3(def.look_at),
+        vec3(def.vup),
+        def.defocus_angle,
+        def.focus_dist,
+    );
+
+    camera.russian_roulette_depth = def.russian_roulette_depth;
+    camera.shutter_open = def.shutter_open;
+    camera.shutter_close = def.shutter_close;
+    camera.sampler = match def.sampler {
+        SamplerDef::Independent => SamplerKind::Independent,
+        SamplerDef::Stratified => SamplerKind::Stratified,
+        SamplerDef::Halton => SamplerKind::Halton,
+        SamplerDef::Sobol => SamplerKind::Sobol,
+        SamplerDef::BlueNoise => SamplerKind::BlueNoise,
+    };
+    camera.adaptive = def
+        .adaptive
+        .map(|adaptive| AdaptiveSampling::new(adaptive.threshold, adaptive.min_samples));
+
+    if let Some(background) = def.background {
+        camera.background = match background {
+            BackgroundDef::Black => Background::Black,
+            BackgroundDef::Solid { colour } => Background::Solid(vec3(colour)),
+            BackgroundDef::Gradient { bottom, top } => Background::Gradient {
+                bottom: vec3(bottom),
+                top: vec3(top),
+            },
+        };
+    }
+
+    Ok(camera)
+}
+
+fn build_material(src: &str, def: Spanned<MaterialDef>) -> Result<MaterialEnum, SceneError> {
+    let span = def.span();
+    let material = match def.into_inner() {
+        MaterialDef::Lambertian { albedo } => {
+            MaterialEnum::Lambertian(Lambertian::new(vec3(albedo)))
+        }
+        MaterialDef::Metal { albedo, fuzz } => {
+            if fuzz < 0.0 {
+                return Err(SceneError::at(src, span, "metal fuzz must not be negative"));
+            }
+            MaterialEnum::Metal(Metal::new(vec3(albedo), fuzz))
+        }
+        MaterialDef::Dielectric { refraction_index } => {
+            if refraction_index <= 0.0 {
+                return Err(SceneError::at(
+                    src,
+                    span,
+                    "dielectric refraction_index must be positive",
+                ));
+            }
+            MaterialEnum::Dielectric(Dielectric::new(refraction_index))
+        }
+        MaterialDef::DiffuseLight { emit } => {
+            MaterialEnum::DiffuseLight(DiffuseLight::new(vec3(emit)))
+        }
+        MaterialDef::Isotropic { albedo } => MaterialEnum::Isotropic(Isotropic::new(vec3(albedo))),
+    };
+
+    Ok(material)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENE: &str = r#"
+[camera]
+image_width = 64
+look_from = [13, 2, 3]
+look_at = [0, 0, 0]
+
+[materials.ground]
+type = "lambertian"
+albedo = [0.5, 0.5, 0.5]
+
+[materials.glass]
+type = "dielectric"
+refraction_index = 1.5
+
+[[objects]]
+type = "sphere"
+center = [0, -1000, 0]
+radius = 1000
+material = "ground"
+
+[[objects]]
+type = "sphere"
+center = [0, 1, 0]
+radius = 1
+material = "glass"
+"#;
+
+    #[test]
+    fn test_parses_scene() {
+        let scene = parse_scene(SCENE).unwrap();
+        assert_eq!(scene.camera.image_width, 64);
+        assert_eq!(scene.world.len(), 2);
+    }
+
+    #[test]
+    fn test_unknown_material_reports_line() {
+        let src = SCENE.replace("material = \"glass\"", "material = \"glas\"");
+        let err = parse_scene(&src).err().unwrap();
+        assert_eq!(err.line, Some(21));
+        assert!(err.message.contains("glas"));
+    }
+
+    #[test]
+    fn test_parses_adaptive_sampling() {
+        let src = SCENE.replace(
+            "image_width = 64\n",
+            "image_width = 64\nadaptive = { threshold = 0.05 }\n",
+        );
+        let scene = parse_scene(&src).unwrap();
+        assert_eq!(
+            scene.camera.adaptive,
+            Some(AdaptiveSampling::new(
+                0.05,
+                AdaptiveSampling::DEFAULT_MIN_SAMPLES
+            ))
+        );
+
+        let src = src.replace("threshold = 0.05", "threshold = 0");
+        assert!(parse_scene(&src).is_err());
+    }
+
+    #[test]
+    fn test_rejects_non_positive_max_depth() {
+        let with_depth = |depth: &str| {
+            SCENE.replace(
+                "image_width = 64\n",
+                &format!("image_width = 64\nmax_depth = {}\n", depth),
+            )
+        };
+        assert_eq!(parse_scene(&with_depth("1")).unwrap().camera.max_depth, 1);
+        let err = parse_scene(&with_depth("0")).err().unwrap();
+        assert_eq!(err.line, Some(2));
+        assert!(err.message.contains("max_depth"));
+        assert!(parse_scene(&with_depth("-3")).is_err());
+    }
+
+    #[test]
+    fn test_rejects_degenerate_camera_entries() {
+        let with_entry = |entry: &str| {
+            SCENE.replace(
+                "image_width = 64\n",
+                &format!("image_width = 64\n{}\n", entry),
+            )
+        };
+        for (entry, message) in [
+            ("vup = [13, 2, 3]", "vup"),
+            ("vup = [0, 0, 0]", "vup"),
+            ("focus_dist = 0", "focus_dist"),
+            ("focus_dist = -2", "focus_dist"),
+            ("aspect_ratio = nan", "aspect_ratio"),
+            ("aspect_ratio = inf", "aspect_ratio"),
+            ("adaptive = { threshold = nan }", "threshold"),
+            ("adaptive = { threshold = inf }", "threshold"),
+        ] {
+            let err = parse_scene(&with_entry(entry)).err().unwrap();
+            assert_eq!(err.line, Some(2), "{}", entry);
+            assert!(err.message.contains(message), "{}: {}", entry, err.message);
+        }
+        assert!(parse_scene(&with_entry("vup = [0, 0, 1]\nfocus_dist = 2")).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_shutter_outside_motion_range() {
+        let with_shutter = |open: &str, close: &str| {
+            SCENE.replace(
+                "image_width = 64\n",
+                &format!(
+                    "image_width = 64\nshutter_open = {}\nshutter_close = {}\n",
+                    open, close
+                ),
+            )
+        };
+        assert!(parse_scene(&with_shutter("0.25", "0.75")).is_ok());
+        assert!(parse_scene(&with_shutter("0.5", "3.0")).is_err());
+        assert!(parse_scene(&with_shutter("-0.5", "0.5")).is_err());
+    }
+
+    #[test]
+    fn test_parses_medium() {
+        let src = format!(
+            "{}{}",
+            SCENE,
+            r#"
+[materials.fog]
+type = "isotropic"
+albedo = [1, 1, 1]
+
+[[objects]]
+type = "medium"
+density = 0.2
+boundary = { type = "sphere", center = [0, 1, 0], radius = 2, material = "fog" }
+"#
+        );
+        let scene = parse_scene(&src).unwrap();
+        assert_eq!(scene.world.len(), 3);
+
+        let src = src.replace("material = \"fog\"", "material = \"ground\"");
+        let err = parse_scene(&src).err().unwrap();
+        assert!(err.message.contains("must be isotropic"));
+    }
+
+    #[test]
+    fn test_imports_obj_and_keeps_its_diagnostics() {
+        let path =
+            std::env::temp_dir().join(format!("ray-tracing-{}-scene.obj", std::process::id()));
+        fs::write(&path, "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt bad\nf 1 2 3\n").unwrap();
+        let with_obj = |path: &Path| {
+            format!(
+                "{}\n[[objects]]\ntype = \"obj\"\npath = {:?}\nmaterial = \"ground\"\n",
+                SCENE,
+                path.display().to_string()
+            )
+        };
+
+        let scene = parse_scene(&with_obj(&path)).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(scene.world.len(), 3);
+        assert_eq!(scene.diagnostics.len(), 1);
+        assert_eq!(scene.diagnostics[0].line, 4);
+
+        let err = parse_scene(&with_obj(&path)).err().unwrap();
+        assert_eq!(err.line, Some(27));
+        assert!(err.message.contains("could not read OBJ file"));
+    }
+
+    #[test]
+    fn test_collects_emissive_objects_as_lights() {
+        let src = format!(
+            "{}{}",
+            SCENE,
+            r#"
+[materials.lamp]
+type = "diffuse_light"
+emit = [4, 4, 4]
+
+[[objects]]
+type = "quad"
+corner = [-1, 5, -1]
+u = [2, 0, 0]
+v = [0, 0, 2]
+material = "lamp"
+"#
+        );
+        let scene = parse_scene(&src).unwrap();
+        assert_eq!(scene.world.len(), 3);
+        assert_eq!(scene.lights.len(), 1);
+    }
+
+    #[test]
+    fn test_rejects_degenerate_quad() {
+        let with_quad = |u: &str, v: &str| {
+            format!(
+                "{}\n[[objects]]\ntype = \"quad\"\ncorner = [0, 0, 0]\nu = {}\nv = {}\nmaterial = \"ground\"\n",
+                SCENE, u, v
+            )
+        };
+        assert!(parse_scene(&with_quad("[1, 0, 0]", "[0, 1, 0]")).is_ok());
+        let err = parse_scene(&with_quad("[1, 0, 0]", "[-2, 0, 0]"))
+            .err()
+            .unwrap();
+        assert!(err.message.contains("parallel"));
+        assert!(parse_scene(&with_quad("[0, 0, 0]", "[0, 1, 0]")).is_err());
+    }
+
+    #[test]
+    fn test_syntax_error_reports_line() {
+        let src = SCENE.replace("radius = 1\n", "radius = \n");
+        let err = parse_scene(&src).err().unwrap();
+        assert_eq!(err.line, Some(24));
+    }
+}
@@ -1,31 +1,63 @@
 use crate::{
-    hittable::Hittable, interval::Interval, material::MaterialEnum, ray::Point3, vec3::Vec3,
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    interval::Interval,
+    material::MaterialEnum,
+    ray::{Point3, Ray},
+    vec3::{tangent_frame, uniform_sphere_direction, Vec3},
 };
 
 pub struct Sphere {
-    center: Point3,
+    center: Ray,
     radius: f64,
     material: MaterialEnum,
+    bbox: Aabb,
 }
 
 impl Sphere {
     pub fn new(center: Point3, radius: f64, material: MaterialEnum) -> Self {
+        let rvec = Vec3::new(radius.abs(), radius.abs(), radius.abs());
         Self {
-            center,
+            center: Ray::new(center, Vec3::default()),
             radius,
             material,
+            bbox: Aabb::from_points(center - rvec, center + rvec),
         }
     }
+
+    /// A sphere whose centre moves linearly from `center1` at time 0 to `center2` at time 1.
+    pub fn moving(center1: Point3, center2: Point3, radius: f64, material: MaterialEnum) -> Self {
+        let rvec = Vec3::new(radius.abs(), radius.abs(), radius.abs());
+        let box1 = Aabb::from_points(center1 - rvec, center1 + rvec);
+        let box2 = Aabb::from_points(center2 - rvec, center2 + rvec);
+        Self {
+            center: Ray::new(center1, center2 - center1),
+            radius,
+            material,
+            bbox: Aabb::enclosing(&box1, &box2),
+        }
+    }
+
+    fn get_sphere_uv(p: Point3) -> (f64, f64) {
+        let theta = (-p.y()).acos();
+        let phi = (-p.z()).atan2(p.x()) + std::f64::consts::PI;
+
+        (
+            phi / (2.0 * std::f64::consts::PI),
+            theta / std::f64::consts::PI,
+        )
+    }
 }
 
 impl Hittable for Sphere {
-    fn hit(
-        &self,
+    fn hit<'a>(
+        &'a self,
         r: &crate::ray::Ray,
         interval: Interval,
-        rec: &mut crate::hittable::HitRecord,
+        rec: &mut crate::hittable::HitRecord<'a>,
     ) -> bool {
-        let oc = self.center - r.origin();
+        let current_center = self.center.at(r.time());
+        let oc = current_center - r.origin();
         let a = r.direction().length_squared();
         let h = Vec3::dot(r.direction(), oc);
         let c = oc.length_squared() - self.radius * self.radius;
@@ -47,10 +79,94 @@ impl Hittable for Sphere {
 
         rec.t = root;
         rec.p = r.at(rec.t);
-        let outward_normal = (rec.p - self.center) / self.radius;
+        let outward_normal = (rec.p - current_center) / self.radius;
         rec.set_face_normal(r, outward_normal);
-        rec.material = self.material;
+        (rec.u, rec.v) = Sphere::get_sphere_uv(outward_normal);
+        rec.material = &self.material;
 
         true
     }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox.clone()
+    }
+
+    /// Uniform over the cone of directions subtended by the sphere; moving spheres
+    /// are sampled at their time-zero position.
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        let mut rec = HitRecord::default();
+        if !self.hit(
+            &Ray::new(origin, direction),
+            Interval::new(0.001, f64::INFINITY),
+            &mut rec,
+        ) {
+            return 0.0;
+        }
+
+        let distance_squared = (self.center.at(0.0) - origin).length_squared();
+        let radius_squared = self.radius * self.radius;
+        if distance_squared <= radius_squared {
+            return 0.0;
+        }
+        let cos_theta_max = (1.0 - radius_squared / distance_squared).sqrt();
+        let solid_angle = 2.0 * std::f64::consts::PI * (1.0 - cos_theta_max);
+
+        1.0 / solid_angle
+    }
+
+    fn random(&self, origin: Point3, u: (f64, f64)) -> Vec3 {
+        let direction = self.center.at(0.0) - origin;
+        let distance_squared = direction.length_squared();
+        let radius_squared = self.radius * self.radius;
+        if distance_squared <= radius_squared {
+            return uniform_sphere_direction(u);
+        }
+
+        let cos_theta_max = (1.0 - radius_squared / distance_squared).sqrt();
+        let z = 1.0 + u.0 * (cos_theta_max - 1.0);
+        let phi = 2.0 * std::f64::consts::PI * u.1;
+        let sin_theta = (1.0 - z * z).sqrt();
+
+        let w = direction.unit_vector();
+        let (u, v) = tangent_frame(w);
+        sin_theta * phi.cos() * u + sin_theta * phi.sin() * v + z * w
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_moving_sphere_follows_its_path_within_its_bounds() {
+        let sphere = Sphere::moving(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(0.0, 2.0, 0.0),
+            0.5,
+            MaterialEnum::default(),
+        );
+        let interval = Interval::new(0.001, f64::INFINITY);
+        let origin = Point3::new(0.0, 2.0, 5.0);
+        let direction = Vec3::new(0.0, 0.0, -1.0);
+
+        let mut rec = HitRecord::default();
+        assert!(!sphere.hit(
+            &Ray::with_time(origin, direction, 0.0),
+            interval.clone(),
+            &mut rec
+        ));
+        assert!(sphere.hit(&Ray::with_time(origin, direction, 1.0), interval, &mut rec));
+        assert!((rec.p - Point3::new(0.0, 2.0, 0.5)).length() < 1e-9);
+
+        let bbox = sphere.bounding_box();
+        for t in [0.0, 0.5, 1.0] {
+            let center = Point3::new(0.0, 2.0 * t, 0.0);
+            for p in [
+                center - Vec3::new(0.5, 0.5, 0.5),
+                center + Vec3::new(0.5, 0.5, 0.5),
+            ] {
+                assert!(bbox.x.contains(p.x()) && bbox.y.contains(p.y()) && bbox.z.contains(p.z()));
+            }
+        }
+    }
 }
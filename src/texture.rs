@@ -0,0 +1,310 @@
+use std::{path::Path, sync::Arc};
+
+use crate::{
+    colour::{srgb_to_linear, Colour},
+    interval::Interval,
+    perlin::Perlin,
+    ray::Point3,
+};
+
+pub trait Texture: Clone {
+    fn value(&self, u: f64, v: f64, p: Point3) -> Colour;
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct SolidColour {
+    albedo: Colour,
+}
+
+impl SolidColour {
+    pub fn new(albedo: Colour) -> Self {
+        Self { albedo }
+    }
+}
+
+impl Texture for SolidColour {
+    fn value(&self, _u: f64, _v: f64, _p: Point3) -> Colour {
+        self.albedo
+    }
+}
+
+#[derive(Clone)]
+pub struct CheckerTexture {
+    inv_scale: f64,
+    even: Arc<TextureEnum>,
+    odd: Arc<TextureEnum>,
+}
+
+impl CheckerTexture {
+    pub fn new(scale: f64, even: TextureEnum, odd: TextureEnum) -> Self {
+        Self {
+            inv_scale: 1.0 / scale,
+            even: Arc::new(even),
+            odd: Arc::new(odd),
+        }
+    }
+
+    pub fn from_colours(scale: f64, even: Colour, odd: Colour) -> Self {
+        Self::new(
+            scale,
+            TextureEnum::Solid(SolidColour::new(even)),
+            TextureEnum::Solid(SolidColour::new(odd)),
+        )
+    }
+}
+
+impl Texture for CheckerTexture {
+    fn value(&self, u: f64, v: f64, p: Point3) -> Colour {
+        let x = (self.inv_scale * p.x()).floor() as i64;
+        let y = (self.inv_scale * p.y()).floor() as i64;
+        let z = (self.inv_scale * p.z()).floor() as i64;
+
+        match (x + y + z) % 2 == 0 {
+            true => self.even.value(u, v, p),
+            false => self.odd.value(u, v, p),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum NoiseStyle {
+    Smooth,
+    Turbulence,
+    Marble,
+    Wood,
+}
+
+#[derive(Clone)]
+pub struct NoiseTexture {
+    noise: Arc<Perlin>,
+    scale: f64,
+    style: NoiseStyle,
+    albedo: Colour,
+}
+
+impl NoiseTexture {
+    pub fn new(style: NoiseStyle, scale: f64) -> Self {
+        Self::tinted(style, scale, Colour::new(1.0, 1.0, 1.0))
+    }
+
+    pub fn tinted(style: NoiseStyle, scale: f64, albedo: Colour) -> Self {
+        Self {
+            noise: Arc::new(Perlin::new()),
+            scale,
+            style,
+            albedo,
+        }
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: f64, _v: f64, p: Point3) -> Colour {
+        let intensity = match self.style {
+            NoiseStyle::Smooth => 0.5 * (1.0 + self.noise.noise(self.scale * p)),
+            NoiseStyle::Turbulence => self.noise.turb(self.scale * p, 7),
+            NoiseStyle::Marble => {
+                0.5 * (1.0 + (self.scale * p.z() + 10.0 * self.noise.turb(p, 7)).sin())
+            }
+            NoiseStyle::Wood => {
+                let r = (p.x() * p.x() + p.z() * p.z()).sqrt();
+                let rings = self.scale * r + 2.0 * self.noise.turb(p, 7);
+                0.6 + 0.4 * (rings - rings.floor())
+            }
+        };
+
+        intensity * self.albedo
+    }
+}
+
+struct ImageData {
+    width: usize,
+    height: usize,
+    pixels: Vec<Colour>,
+}
+
+#[derive(Clone)]
+pub struct ImageTexture {
+    image: Arc<ImageData>,
+}
+
+impl ImageTexture {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, image::ImageError> {
+        let rgb = image::open(path)?.into_rgb8();
+        let (width, height) = rgb.dimensions();
+
+        let pixels = rgb
+            .pixels()
+            .map(|pixel| {
+                Colour::new(
+                    gamma_to_linear(pixel[0]),
+                    gamma_to_linear(pixel[1]),
+                    gamma_to_linear(pixel[2]),
+                )
+            })
+            .collect();
+
+        Ok(Self {
+            image: Arc::new(ImageData {
+                width: width as usize,
+                height: height as usize,
+                pixels,
+            }),
+        })
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f64, v: f64, _p: Point3) -> Colour {
+        if self.image.pixels.is_empty() {
+            return Colour::new(0.0, 1.0, 1.0);
+        }
+
+        let unit = Interval::new(0.0, 1.0);
+        let u = unit.clamp(u);
+        let v = 1.0 - unit.clamp(v);
+
+        let i = ((u * self.image.width as f64) as usize).min(self.image.width - 1);
+        let j = ((v * self.image.height as f64) as usize).min(self.image.height - 1);
+
+        self.image.pixels[j * self.image.width + i]
+    }
+}
+
+fn gamma_to_linear(byte: u8) -> f64 {
+    srgb_to_linear(byte as f64 / 255.0)
+}
+
+#[derive(Clone)]
+pub enum TextureEnum {
+    Solid(SolidColour),
+    Checker(CheckerTexture),
+    Noise(NoiseTexture),
+    Image(ImageTexture),
+}
+
+impl Texture for TextureEnum {
+    fn value(&self, u: f64, v: f64, p: Point3) -> Colour {
+        match self {
+            TextureEnum::Solid(t) => t.value(u, v, p),
+            TextureEnum::Checker(t) => t.value(u, v, p),
+            TextureEnum::Noise(t) => t.value(u, v, p),
+            TextureEnum::Image(t) => t.value(u, v, p),
+        }
+    }
+}
+
+impl Default for TextureEnum {
+    fn default() -> Self {
+        TextureEnum::Solid(SolidColour::default())
+    }
+}
+
+impl From<Colour> for TextureEnum {
+    fn from(albedo: Colour) -> Self {
+        TextureEnum::Solid(SolidColour::new(albedo))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn same(a: Colour, b: Colour) -> bool {
+        (a - b).length() < 1e-12
+    }
+
+    #[test]
+    fn test_solid_colour_ignores_coordinates() {
+        let texture = TextureEnum::from(Colour::new(0.1, 0.2, 0.3));
+        for (u, v, p) in [
+            (0.0, 0.0, Point3::default()),
+            (0.7, 0.2, Point3::new(-4.0, 9.0, 1.5)),
+        ] {
+            assert!(same(texture.value(u, v, p), Colour::new(0.1, 0.2, 0.3)));
+        }
+    }
+
+    #[test]
+    fn test_checker_alternates_between_cells() {
+        let even = Colour::new(1.0, 1.0, 1.0);
+        let odd = Colour::new(0.0, 0.0, 0.0);
+        let checker = CheckerTexture::from_colours(0.5, even, odd);
+
+        assert!(same(
+            checker.value(0.0, 0.0, Point3::new(0.1, 0.1, 0.1)),
+            even
+        ));
+        assert!(same(
+            checker.value(0.0, 0.0, Point3::new(0.6, 0.1, 0.1)),
+            odd
+        ));
+        assert!(same(
+            checker.value(0.0, 0.0, Point3::new(0.6, 0.6, 0.1)),
+            even
+        ));
+        // Cells continue across the origin rather than mirroring it.
+        assert!(same(
+            checker.value(0.0, 0.0, Point3::new(-0.1, 0.1, 0.1)),
+            odd
+        ));
+        assert!(same(
+            checker.value(0.0, 0.0, Point3::new(-0.6, 0.1, 0.1)),
+            even
+        ));
+    }
+
+    #[test]
+    fn test_image_maps_v_upwards_and_clamps() {
+        let texture = ImageTexture {
+            image: Arc::new(ImageData {
+                width: 2,
+                height: 2,
+                pixels: vec![
+                    Colour::new(1.0, 0.0, 0.0),
+                    Colour::new(0.0, 1.0, 0.0),
+                    Colour::new(0.0, 0.0, 1.0),
+                    Colour::new(1.0, 1.0, 1.0),
+                ],
+            }),
+        };
+        let p = Point3::default();
+
+        assert!(same(
+            texture.value(0.25, 0.75, p),
+            Colour::new(1.0, 0.0, 0.0)
+        ));
+        assert!(same(
+            texture.value(0.75, 0.75, p),
+            Colour::new(0.0, 1.0, 0.0)
+        ));
+        assert!(same(
+            texture.value(0.25, 0.25, p),
+            Colour::new(0.0, 0.0, 1.0)
+        ));
+        assert!(same(
+            texture.value(1.5, -0.5, p),
+            Colour::new(1.0, 1.0, 1.0)
+        ));
+
+        let missing = ImageTexture {
+            image: Arc::new(ImageData {
+                width: 0,
+                height: 0,
+                pixels: Vec::new(),
+            }),
+        };
+        assert!(same(missing.value(0.5, 0.5, p), Colour::new(0.0, 1.0, 1.0)));
+    }
+
+    #[test]
+    fn test_texture_bytes_round_trip_through_srgb_output() {
+        assert_eq!(gamma_to_linear(0), 0.0);
+        assert_eq!(gamma_to_linear(255), 1.0);
+        assert!((gamma_to_linear(128) - 0.2158).abs() < 1e-4);
+
+        for byte in 0..=255 {
+            let linear = gamma_to_linear(byte);
+            assert_eq!(Colour::new(linear, linear, linear).to_rgb8(), [byte; 3]);
+        }
+    }
+}
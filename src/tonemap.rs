@@ -0,0 +1,115 @@
+use crate::{colour::Colour, interval::Interval};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ToneMapper {
+    Clamp,
+    Reinhard,
+    ExtendedReinhard { white_point: f64 },
+    Aces,
+    Hable,
+}
+
+impl ToneMapper {
+    pub fn map(&self, x: f64) -> f64 {
+        let x = x.max(0.0);
+        match self {
+            ToneMapper::Clamp => x,
+            ToneMapper::Reinhard => x / (1.0 + x),
+            ToneMapper::ExtendedReinhard { white_point } => {
+                x * (1.0 + x / (white_point * white_point)) / (1.0 + x)
+            }
+            ToneMapper::Aces => {
+                // Narkowicz's fit of the ACES reference rendering transform.
+                let (a, b, c, d, e) = (2.51, 0.03, 2.43, 0.59, 0.14);
+                (x * (a * x + b)) / (x * (c * x + d) + e)
+            }
+            ToneMapper::Hable => {
+                let white = 11.2;
+                hable_partial(2.0 * x) / hable_partial(white)
+            }
+        }
+    }
+}
+
+fn hable_partial(x: f64) -> f64 {
+    let (a, b, c, d, e, f) = (0.15, 0.50, 0.10, 0.20, 0.02, 0.30);
+    ((x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f)) - e / f
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DisplayTransform {
+    pub tone_mapper: ToneMapper,
+    pub exposure: f64,
+}
+
+impl DisplayTransform {
+    pub fn new(tone_mapper: ToneMapper, exposure: f64) -> Self {
+        Self {
+            tone_mapper,
+            exposure,
+        }
+    }
+
+    pub fn apply(&self, colour: Colour) -> Colour {
+        let scale = 2f64.powf(self.exposure);
+        let unit = Interval::new(0.0, 1.0);
+
+        Colour::new(
+            unit.clamp(self.tone_mapper.map(scale * colour.x())),
+            unit.clamp(self.tone_mapper.map(scale * colour.y())),
+            unit.clamp(self.tone_mapper.map(scale * colour.z())),
+        )
+    }
+
+    pub fn to_rgb8(&self, colour: Colour) -> [u8; 3] {
+        self.apply(colour).to_rgb8()
+    }
+}
+
+impl Default for DisplayTransform {
+    fn default() -> Self {
+        Self::new(ToneMapper::Clamp, 0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAPPERS: [ToneMapper; 5] = [
+        ToneMapper::Clamp,
+        ToneMapper::Reinhard,
+        ToneMapper::ExtendedReinhard { white_point: 4.0 },
+        ToneMapper::Aces,
+        ToneMapper::Hable,
+    ];
+
+    #[test]
+    fn test_output_is_display_range() {
+        for mapper in MAPPERS {
+            let display = DisplayTransform::new(mapper, 0.0);
+            for x in [0.0, 0.01, 0.5, 1.0, 4.0, 100.0, 1e6] {
+                let c = display.apply(Colour::new(x, x, x));
+                assert!((0.0..=1.0).contains(&c.x()), "{:?} at {}", mapper, x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_monotonic() {
+        for mapper in MAPPERS {
+            let mut previous = mapper.map(0.0);
+            for i in 1..200 {
+                let current = mapper.map(i as f64 * 0.05);
+                assert!(current >= previous, "{:?} at {}", mapper, i);
+                previous = current;
+            }
+        }
+    }
+
+    #[test]
+    fn test_extended_reinhard_white_point_maps_to_one() {
+        let mapper = ToneMapper::ExtendedReinhard { white_point: 4.0 };
+        assert!((mapper.map(4.0) - 1.0).abs() < 1e-12);
+    }
+}
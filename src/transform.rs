@@ -0,0 +1,416 @@
+use std::{ops::Mul, sync::Arc};
+
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    interval::Interval,
+    ray::{Point3, Ray},
+    utils::degrees_to_radians,
+    vec3::Vec3,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mat4 {
+    m: [[f64; 4]; 4],
+}
+
+impl Mat4 {
+    pub fn new(m: [[f64; 4]; 4]) -> Self {
+        Self { m }
+    }
+
+    pub fn identity() -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            row[i] = 1.0;
+        }
+        Self { m }
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.m[j][i];
+            }
+        }
+        Self { m }
+    }
+
+    /// Gauss-Jordan elimination with partial pivoting; `None` if the matrix is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut a = self.m;
+        let mut inv = Mat4::identity().m;
+
+        for col in 0..4 {
+            let pivot = (col..4).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+            if a[pivot][col].abs() < 1e-12 {
+                return None;
+            }
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+
+            let scale = 1.0 / a[col][col];
+            for k in 0..4 {
+                a[col][k] *= scale;
+                inv[col][k] *= scale;
+            }
+
+            for row in 0..4 {
+                if row == col {
+                    continue;
+                }
+                let factor = a[row][col];
+                for k in 0..4 {
+                    a[row][k] -= factor * a[col][k];
+                    inv[row][k] -= factor * inv[col][k];
+                }
+            }
+        }
+
+        Some(Self { m: inv })
+    }
+
+    pub fn transform_point(&self, p: Point3) -> Point3 {
+        let m = &self.m;
+        let x = m[0][0] * p.x() + m[0][1] * p.y() + m[0][2] * p.z() + m[0][3];
+        let y = m[1][0] * p.x() + m[1][1] * p.y() + m[1][2] * p.z() + m[1][3];
+        let z = m[2][0] * p.x() + m[2][1] * p.y() + m[2][2] * p.z() + m[2][3];
+        let w = m[3][0] * p.x() + m[3][1] * p.y() + m[3][2] * p.z() + m[3][3];
+
+        match w == 1.0 {
+            true => Point3::new(x, y, z),
+            false => Point3::new(x, y, z) / w,
+        }
+    }
+
+    pub fn transform_vector(&self, v: Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::new(
+            m[0][0] * v.x() + m[0][1] * v.y() + m[0][2] * v.z(),
+            m[1][0] * v.x() + m[1][1] * v.y() + m[1][2] * v.z(),
+            m[2][0] * v.x() + m[2][1] * v.y() + m[2][2] * v.z(),
+        )
+    }
+}
+
+impl Mul for Mat4 {
+    type Output = Mat4;
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.m[i][k] * rhs.m[k][j]).sum();
+            }
+        }
+        Mat4 { m }
+    }
+}
+
+/// An affine transform stored alongside its inverse so rays can be moved
+/// into object space without re-inverting per hit.
+#[derive(Clone, Copy, Debug)]
+pub struct Transform {
+    matrix: Mat4,
+    inverse: Mat4,
+}
+
+impl Transform {
+    pub fn new(matrix: Mat4) -> Option<Self> {
+        Some(Self {
+            matrix,
+            inverse: matrix.inverse()?,
+        })
+    }
+
+    pub fn identity() -> Self {
+        Self {
+            matrix: Mat4::identity(),
+            inverse: Mat4::identity(),
+        }
+    }
+
+    pub fn translate(offset: Vec3) -> Self {
+        let (x, y, z) = (offset.x(), offset.y(), offset.z());
+        Self {
+            matrix: Mat4::new([
+                [1.0, 0.0, 0.0, x],
+                [0.0, 1.0, 0.0, y],
+                [0.0, 0.0, 1.0, z],
+                [0.0, 0.0, 0.0, 1.0],
+            ]),
+            inverse: Mat4::new([
+                [1.0, 0.0, 0.0, -x],
+                [0.0, 1.0, 0.0, -y],
+                [0.0, 0.0, 1.0, -z],
+                [0.0, 0.0, 0.0, 1.0],
+            ]),
+        }
+    }
+
+    /// Returns `None` if any scale factor is zero or not finite, since the transform
+    /// would not be invertible.
+    pub fn scale(factors: Vec3) -> Option<Self> {
+        let (x, y, z) = (factors.x(), factors.y(), factors.z());
+        if [x, y, z].iter().any(|&f| f == 0.0 || !f.is_finite()) {
+            return None;
+        }
+        Some(Self {
+            matrix: Mat4::new([
+                [x, 0.0, 0.0, 0.0],
+                [0.0, y, 0.0, 0.0],
+                [0.0, 0.0, z, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ]),
+            inverse: Mat4::new([
+                [1.0 / x, 0.0, 0.0, 0.0],
+                [0.0, 1.0 / y, 0.0, 0.0],
+                [0.0, 0.0, 1.0 / z, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ]),
+        })
+    }
+
+    /// Rotation by `degrees` about `axis`, counter-clockwise looking down the axis.
+    pub fn rotate(axis: Vec3, degrees: f64) -> Self {
+        let a = axis.unit_vector();
+        let theta = degrees_to_radians(degrees);
+        let (sin, cos) = theta.sin_cos();
+        let t = 1.0 - cos;
+        let (x, y, z) = (a.x(), a.y(), a.z());
+
+        let matrix = Mat4::new([
+            [
+                t * x * x + cos,
+                t * x * y - sin * z,
+                t * x * z + sin * y,
+                0.0,
+            ],
+            [
+                t * x * y + sin * z,
+                t * y * y + cos,
+                t * y * z - sin * x,
+                0.0,
+            ],
+            [
+                t * x * z - sin * y,
+                t * y * z + sin * x,
+                t * z * z + cos,
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+
+        Self {
+            matrix,
+            inverse: matrix.transpose(),
+        }
+    }
+
+    pub fn rotate_x(degrees: f64) -> Self {
+        Self::rotate(Vec3::new(1.0, 0.0, 0.0), degrees)
+    }
+
+    pub fn rotate_y(degrees: f64) -> Self {
+        Self::rotate(Vec3::new(0.0, 1.0, 0.0), degrees)
+    }
+
+    pub fn rotate_z(degrees: f64) -> Self {
+        Self::rotate(Vec3::new(0.0, 0.0, 1.0), degrees)
+    }
+
+    /// Applies `self` first and then `next`.
+    pub fn then(self, next: Transform) -> Self {
+        Self {
+            matrix: next.matrix * self.matrix,
+            inverse: self.inverse * next.inverse,
+        }
+    }
+
+    pub fn matrix(&self) -> &Mat4 {
+        &self.matrix
+    }
+
+    pub fn inverse(&self) -> &Mat4 {
+        &self.inverse
+    }
+
+    pub fn point(&self, p: Point3) -> Point3 {
+        self.matrix.transform_point(p)
+    }
+
+    pub fn vector(&self, v: Vec3) -> Vec3 {
+        self.matrix.transform_vector(v)
+    }
+
+    pub fn normal(&self, n: Vec3) -> Vec3 {
+        self.inverse.transpose().transform_vector(n)
+    }
+
+    pub fn bounds(&self, bbox: &Aabb) -> Aabb {
+        if !bbox.is_bounded() {
+            return Aabb::new(
+                Interval::universe(),
+                Interval::universe(),
+                Interval::universe(),
+            );
+        }
+
+        let mut min = Point3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+        let mut max = Point3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for i in 0..8 {
+            let pick = |axis: &Interval, bit: usize| match i & bit {
+                0 => axis.min(),
+                _ => axis.max(),
+            };
+            let corner = self.point(Point3::new(
+                pick(&bbox.x, 1),
+                pick(&bbox.y, 2),
+                pick(&bbox.z, 4),
+            ));
+            for axis in 0..3 {
+                min[axis] = min[axis].min(corner[axis]);
+                max[axis] = max[axis].max(corner[axis]);
+            }
+        }
+
+        Aabb::from_points(min, max)
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// Places a shared hittable in the world through an affine transform.
+pub struct Instance {
+    object: Arc<dyn Hittable>,
+    transform: Transform,
+    bbox: Aabb,
+    inverse_determinant: f64,
+}
+
+impl Instance {
+    pub fn new(object: Arc<dyn Hittable>, transform: Transform) -> Self {
+        let bbox = transform.bounds(&object.bounding_box());
+        let inverse = transform.inverse();
+        let inverse_determinant = Vec3::dot(
+            inverse.transform_vector(Vec3::new(1.0, 0.0, 0.0)),
+            Vec3::cross(
+                inverse.transform_vector(Vec3::new(0.0, 1.0, 0.0)),
+                inverse.transform_vector(Vec3::new(0.0, 0.0, 1.0)),
+            ),
+        )
+        .abs();
+        Self {
+            object,
+            transform,
+            bbox,
+            inverse_determinant,
+        }
+    }
+}
+
+impl Hittable for Instance {
+    fn hit<'a>(&'a self, r: &Ray, interval: Interval, rec: &mut HitRecord<'a>) -> bool {
+        let inverse = self.transform.inverse();
+        let object_ray = Ray::with_time(
+            inverse.transform_point(r.origin()),
+            inverse.transform_vector(r.direction()),
+            r.time(),
+        );
+
+        if !self.object.hit(&object_ray, interval, rec) {
+            return false;
+        }
+
+        rec.p = self.transform.point(rec.p);
+        rec.normal = self.transform.normal(rec.normal).unit_vector();
+
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox.clone()
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        // Directions map to object space through the inverse's linear part `A`, which
+        // stretches solid angle by |det A| / |A d|^3 for a unit direction d.
+        let inverse = self.transform.inverse();
+        let object_direction = inverse.transform_vector(direction.unit_vector());
+        let jacobian = self.inverse_determinant / object_direction.length().powi(3);
+
+        self.object
+            .pdf_value(inverse.transform_point(origin), object_direction)
+            * jacobian
+    }
+
+    fn random(&self, origin: Point3, u: (f64, f64)) -> Vec3 {
+        let object_origin = self.transform.inverse().transform_point(origin);
+        self.transform.vector(self.object.random(object_origin, u))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::MaterialEnum, sphere::Sphere};
+
+    fn assert_near(a: Vec3, b: Vec3) {
+        assert!((a - b).length() < 1e-9, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_inverse_round_trips() {
+        let transform = Transform::scale(Vec3::new(2.0, 3.0, 0.5))
+            .unwrap()
+            .then(Transform::rotate(Vec3::new(1.0, 1.0, 0.0), 37.0))
+            .then(Transform::translate(Vec3::new(1.0, -2.0, 5.0)));
+
+        let general = transform.matrix().inverse().unwrap();
+        let p = Point3::new(0.3, -1.2, 4.0);
+        assert_near(general.transform_point(transform.point(p)), p);
+        assert_near(transform.inverse().transform_point(transform.point(p)), p);
+    }
+
+    #[test]
+    fn test_singular_scale_is_rejected() {
+        assert!(Transform::scale(Vec3::new(1.0, 0.0, 1.0)).is_none());
+        assert!(Transform::scale(Vec3::new(f64::INFINITY, 1.0, 1.0)).is_none());
+        assert!(Transform::new(Mat4::new([[0.0; 4]; 4])).is_none());
+    }
+
+    #[test]
+    fn test_rotate_y_turns_x_into_negative_z() {
+        let transform = Transform::rotate_y(90.0);
+        assert_near(
+            transform.vector(Vec3::new(1.0, 0.0, 0.0)),
+            Vec3::new(0.0, 0.0, -1.0),
+        );
+    }
+
+    #[test]
+    fn test_instance_hits_scaled_and_moved_sphere() {
+        let sphere: Arc<dyn Hittable> =
+            Arc::new(Sphere::new(Point3::default(), 1.0, MaterialEnum::default()));
+        let instance = Instance::new(
+            sphere,
+            Transform::scale(Vec3::new(2.0, 1.0, 1.0))
+                .unwrap()
+                .then(Transform::translate(Vec3::new(10.0, 0.0, 0.0))),
+        );
+
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        let mut rec = HitRecord::default();
+        assert!(instance.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec));
+        assert!((rec.t - 8.0).abs() < 1e-9);
+        assert_near(rec.p, Point3::new(8.0, 0.0, 0.0));
+        assert_near(rec.normal, Vec3::new(-1.0, 0.0, 0.0));
+
+        let bbox = instance.bounding_box();
+        assert!((bbox.x.min() - 8.0).abs() < 1e-9 && (bbox.x.max() - 12.0).abs() < 1e-9);
+    }
+}
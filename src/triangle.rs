@@ -0,0 +1,117 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{area_pdf_to_solid_angle, HitRecord, Hittable},
+    interval::Interval,
+    material::MaterialEnum,
+    ray::{Point3, Ray},
+    vec3::Vec3,
+};
+
+pub struct Triangle {
+    v0: Point3,
+    v1: Point3,
+    v2: Point3,
+    material: MaterialEnum,
+    bbox: Aabb,
+}
+
+impl Triangle {
+    pub fn new(v0: Point3, v1: Point3, v2: Point3, material: MaterialEnum) -> Self {
+        Self {
+            v0,
+            v1,
+            v2,
+            material,
+            bbox: triangle_bounds(v0, v1, v2),
+        }
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit<'a>(&'a self, r: &Ray, interval: Interval, rec: &mut HitRecord<'a>) -> bool {
+        let Some((t, b1, b2)) = intersect(r, self.v0, self.v1, self.v2, &interval) else {
+            return false;
+        };
+
+        rec.t = t;
+        rec.p = r.at(t);
+        rec.u = b1;
+        rec.v = b2;
+        let outward_normal = Vec3::cross(self.v1 - self.v0, self.v2 - self.v0).unit_vector();
+        rec.set_face_normal(r, outward_normal);
+        rec.material = &self.material;
+
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox.clone()
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        let mut rec = HitRecord::default();
+        if !self.hit(
+            &Ray::new(origin, direction),
+            Interval::new(0.001, f64::INFINITY),
+            &mut rec,
+        ) {
+            return 0.0;
+        }
+
+        let area = 0.5 * Vec3::cross(self.v1 - self.v0, self.v2 - self.v0).length();
+        area_pdf_to_solid_angle(direction, &rec, area)
+    }
+
+    fn random(&self, origin: Point3, u: (f64, f64)) -> Vec3 {
+        // Fold samples from the far half of the parallelogram back into the triangle.
+        let (mut b1, mut b2) = u;
+        if b1 + b2 > 1.0 {
+            (b1, b2) = (1.0 - b1, 1.0 - b2);
+        }
+        let p = self.v0 + b1 * (self.v1 - self.v0) + b2 * (self.v2 - self.v0);
+        p - origin
+    }
+}
+
+pub(crate) fn triangle_bounds(v0: Point3, v1: Point3, v2: Point3) -> Aabb {
+    Aabb::enclosing(&Aabb::from_points(v0, v1), &Aabb::from_points(v0, v2))
+}
+
+/// Möller–Trumbore intersection, returning the hit distance and the
+/// barycentric weights of `v1` and `v2`.
+pub(crate) fn intersect(
+    r: &Ray,
+    v0: Point3,
+    v1: Point3,
+    v2: Point3,
+    interval: &Interval,
+) -> Option<(f64, f64, f64)> {
+    let edge1 = v1 - v0;
+    let edge2 = v2 - v0;
+
+    let pvec = Vec3::cross(r.direction(), edge2);
+    let det = Vec3::dot(edge1, pvec);
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+
+    let tvec = r.origin() - v0;
+    let b1 = Vec3::dot(tvec, pvec) * inv_det;
+    if !(0.0..=1.0).contains(&b1) {
+        return None;
+    }
+
+    let qvec = Vec3::cross(tvec, edge1);
+    let b2 = Vec3::dot(r.direction(), qvec) * inv_det;
+    if b2 < 0.0 || b1 + b2 > 1.0 {
+        return None;
+    }
+
+    let t = Vec3::dot(edge2, qvec) * inv_det;
+    if !interval.surrounds(t) {
+        return None;
+    }
+
+    Some((t, b1, b2))
+}
@@ -1,21 +1,32 @@
-use rand::{distributions::Distribution, distributions::Uniform, thread_rng};
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64Mcg;
 
-use crate::vec3::Vec3;
+/// The generator threaded through all sampling code. PCG has a fixed, documented
+/// output stream, so a seed reproduces the same render across builds.
+pub type RenderRng = Pcg64Mcg;
 
-pub fn sample_square() -> Vec3 {
-    Vec3::new(random_double() - 0.5, random_double() - 0.5, 0.0)
+/// An independent generator for one `stream` of a seeded render, such as a pixel.
+pub fn stream_rng(seed: u64, stream: u64) -> RenderRng {
+    RenderRng::seed_from_u64(mix64(seed ^ stream.wrapping_mul(0x9e37_79b9_7f4a_7c15)))
 }
 
-pub fn random_double() -> f64 {
-    let mut rng = thread_rng();
-    let uniform = Uniform::from(0.0..1.0);
-    uniform.sample(&mut rng)
+/// SplitMix64 finaliser, so neighbouring inputs hash to unrelated values.
+pub fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
 }
 
-pub fn random_double_in_range(min: f64, max: f64) -> f64 {
-    let mut rng = thread_rng();
-    let uniform = Uniform::from(min..max);
-    uniform.sample(&mut rng)
+pub fn random_double(rng: &mut RenderRng) -> f64 {
+    rng.gen_range(0.0..1.0)
+}
+
+pub fn random_double_in_range(rng: &mut RenderRng, min: f64, max: f64) -> f64 {
+    rng.gen_range(min..max)
+}
+
+pub fn random_int_in_range(rng: &mut RenderRng, min: i64, max: i64) -> i64 {
+    rng.gen_range(min..=max)
 }
 
 pub fn degrees_to_radians(degrees: f64) -> f64 {
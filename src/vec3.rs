@@ -1,7 +1,7 @@
 use std::fmt;
 use std::ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub};
 
-use crate::utils::{random_double, random_double_in_range};
+use crate::utils::{random_double, random_double_in_range, RenderRng};
 
 #[derive(Copy, Clone, Debug)]
 pub struct Vec3 {
@@ -33,15 +33,15 @@ impl Vec3 {
         self.e[0] * self.e[0] + self.e[1] * self.e[1] + self.e[2] * self.e[2]
     }
 
-    pub fn random() -> Vec3 {
-        Vec3::new(random_double(), random_double(), random_double())
+    pub fn random(rng: &mut RenderRng) -> Vec3 {
+        Vec3::new(random_double(rng), random_double(rng), random_double(rng))
     }
 
-    pub fn random_in_range(min: f64, max: f64) -> Vec3 {
+    pub fn random_in_range(rng: &mut RenderRng, min: f64, max: f64) -> Vec3 {
         Vec3::new(
-            random_double_in_range(min, max),
-            random_double_in_range(min, max),
-            random_double_in_range(min, max),
+            random_double_in_range(rng, min, max),
+            random_double_in_range(rng, min, max),
+            random_double_in_range(rng, min, max),
         )
     }
 
@@ -61,20 +61,20 @@ impl Vec3 {
         self / self.length()
     }
 
-    pub fn random_in_unit_sphere() -> Vec3 {
+    pub fn random_in_unit_sphere(rng: &mut RenderRng) -> Vec3 {
         loop {
-            let p = Vec3::random_in_range(-1.0, 1.0);
+            let p = Vec3::random_in_range(rng, -1.0, 1.0);
             if p.length_squared() < 1.0 {
                 return p;
             }
         }
     }
 
-    pub fn random_in_unit_disk() -> Vec3 {
+    pub fn random_in_unit_disk(rng: &mut RenderRng) -> Vec3 {
         loop {
             let p = Vec3::new(
-                random_double_in_range(-1.0, 1.0),
-                random_double_in_range(-1.0, 1.0),
+                random_double_in_range(rng, -1.0, 1.0),
+                random_double_in_range(rng, -1.0, 1.0),
                 0.0,
             );
             if p.length_squared() < 1.0 {
@@ -83,8 +83,8 @@ impl Vec3 {
         }
     }
 
-    pub fn random_on_hemisphere(self) -> Vec3 {
-        let on_unit_sphere = random_unit_vector();
+    pub fn random_on_hemisphere(self, rng: &mut RenderRng) -> Vec3 {
+        let on_unit_sphere = random_unit_vector(rng);
         if Vec3::dot(on_unit_sphere, self) > 0.0 {
             return on_unit_sphere;
         }
@@ -92,6 +92,10 @@ impl Vec3 {
         -on_unit_sphere
     }
 
+    pub fn max_component(&self) -> f64 {
+        self.e[0].max(self.e[1]).max(self.e[2])
+    }
+
     pub fn near_zero(&self) -> bool {
         let s = 1e-8;
         self.e[0].abs() < s && self.e[1].abs() < s && self.e[2].abs() < s
@@ -110,8 +114,56 @@ impl Vec3 {
     }
 }
 
-pub fn random_unit_vector() -> Vec3 {
-    Vec3::random_in_unit_sphere().unit_vector()
+pub fn random_unit_vector(rng: &mut RenderRng) -> Vec3 {
+    Vec3::random_in_unit_sphere(rng).unit_vector()
+}
+
+/// Maps the uniform sample `(r1, r2)` to a unit vector about +z, distributed with
+/// density cos(θ) / π.
+pub fn cosine_direction((r1, r2): (f64, f64)) -> Vec3 {
+    let phi = 2.0 * std::f64::consts::PI * r1;
+    let x = phi.cos() * r2.sqrt();
+    let y = phi.sin() * r2.sqrt();
+    let z = (1.0 - r2).sqrt();
+
+    Vec3::new(x, y, z)
+}
+
+/// Maps the uniform sample `(r1, r2)` to a unit vector distributed uniformly over
+/// the sphere.
+pub fn uniform_sphere_direction((r1, r2): (f64, f64)) -> Vec3 {
+    let z = 1.0 - 2.0 * r1;
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    let phi = 2.0 * std::f64::consts::PI * r2;
+
+    Vec3::new(r * phi.cos(), r * phi.sin(), z)
+}
+
+/// Maps the uniform sample `(r1, r2)` to a point in the unit disk, keeping
+/// neighbouring samples close together (Shirley and Chiu's concentric mapping).
+pub fn concentric_disk((r1, r2): (f64, f64)) -> Vec3 {
+    let (a, b) = (2.0 * r1 - 1.0, 2.0 * r2 - 1.0);
+    if a == 0.0 && b == 0.0 {
+        return Vec3::new(0.0, 0.0, 0.0);
+    }
+
+    let quarter_pi = std::f64::consts::FRAC_PI_4;
+    let (r, theta) = match a.abs() > b.abs() {
+        true => (a, quarter_pi * (b / a)),
+        false => (b, 2.0 * quarter_pi - quarter_pi * (a / b)),
+    };
+
+    Vec3::new(r * theta.cos(), r * theta.sin(), 0.0)
+}
+
+/// Builds an orthonormal tangent frame around the unit vector `n`.
+pub fn tangent_frame(n: Vec3) -> (Vec3, Vec3) {
+    let helper = match n.x().abs() > 0.9 {
+        true => Vec3::new(0.0, 1.0, 0.0),
+        false => Vec3::new(1.0, 0.0, 0.0),
+    };
+    let tangent = Vec3::cross(helper, n).unit_vector();
+    (tangent, Vec3::cross(n, tangent))
 }
 
 impl Default for Vec3 {